@@ -1,13 +1,54 @@
-use crate::models::package::Package;
-use std::collections::HashSet;
+use crate::core::version;
+use crate::models::package::{DbPackage, Package};
+use std::collections::{HashMap, HashSet};
 
 /// Represents a dependency conflict warning.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DependencyConflict {
     pub ignored_package: String,
+    /// Installed version of the ignored package, when known
+    pub installed_version: Option<String>,
+    /// Updated packages that require the ignored package but whose new
+    /// dependencies are unknown (e.g. AUR packages)
     pub required_by: Vec<String>,
+    /// Dependencies of new versions the installed version does not satisfy
+    pub violations: Vec<ConstraintViolation>,
 }
 
+/// A dependency of a new version that an ignored package's installed version
+/// does not satisfy, e.g. `bar 3.0` depending on `foo>=2.0` while `foo` stays at 1.9.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    pub package: String,
+    pub new_version: String,
+    /// The unsatisfied dependency as declared, e.g. `foo>=2.0`
+    pub constraint: String,
+}
+
+/// A dependency of a package staying at its installed version that an update
+/// takes away, typically a library soname: a foreign package linking
+/// `libicuuc.so=74-64` breaks once `icu` only provides `libicuuc.so=75-64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryBreakage {
+    /// Held-back or foreign package that keeps its installed version
+    pub package: String,
+    /// The dependency that will no longer be satisfied
+    pub dependency: String,
+    /// Package being updated that satisfies the dependency today
+    pub provider: String,
+}
+
+/// A package that must be held back because something it needs is, along
+/// with the packages it holds back in turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoldNode {
+    pub package: String,
+    pub dependents: Vec<HoldNode>,
+}
+
+/// Provided name → `(provider, provided version)`, including each package's own name.
+type ProvisionIndex<'a> = HashMap<&'a str, Vec<(&'a str, Option<&'a str>)>>;
+
 /// Result type for dependency operations
 pub type DependencyResult = Result<Vec<DependencyConflict>, Vec<String>>;
 
@@ -17,6 +58,7 @@ pub type DependencyResult = Result<Vec<DependencyConflict>, Vec<String>>;
 ///
 /// * `all_packages` - All packages available for update
 /// * `ignored_packages` - Package names being ignored
+/// * `local` - Local database records, for what the ignored packages provide
 /// * `get_required_by` - Function returning (`package_deps`, `optional_error_message`)
 ///
 /// # Returns
@@ -29,6 +71,7 @@ pub type DependencyResult = Result<Vec<DependencyConflict>, Vec<String>>;
 pub fn check_conflicts<F>(
     all_packages: &[Package],
     ignored_packages: &[String],
+    local: &[DbPackage],
     mut get_required_by: F,
 ) -> DependencyResult
 where
@@ -36,7 +79,7 @@ where
 {
     let mut warnings = Vec::new();
 
-    let conflicts = detect_conflicts(all_packages, ignored_packages, |pkg| {
+    let conflicts = detect_conflicts(all_packages, ignored_packages, local, |pkg| {
         let (deps, err) = get_required_by(pkg);
         if let Some(e) = err {
             warnings.push(format!("Failed to check dependencies for {pkg}: {e}"));
@@ -53,14 +96,21 @@ where
 
 /// Detects dependency conflicts when packages are ignored.
 ///
-/// Checks if any packages being updated depend on packages being ignored.
-/// This prevents partial upgrade scenarios that could break the system.
+/// Packages being updated whose new dependencies are known (from the sync
+/// database) conflict only if one of those dependencies resolves to an ignored
+/// package, by name or through its installed `provides`, and the installed
+/// version does not satisfy its version constraint. For
+/// packages with unknown new dependencies, any reverse dependency on an
+/// ignored package is reported, since a partial upgrade cannot be ruled out.
 ///
 /// # Arguments
 ///
 /// * `all_packages` - All packages available for update
 /// * `ignored_packages` - Package names being ignored (temporary or permanent)
-/// * `get_required_by` - Function to fetch reverse dependencies for a package
+/// * `local` - Local database records; without one, an ignored package only
+///   satisfies dependencies on its own name
+/// * `get_required_by` - Function to fetch reverse dependencies for a package;
+///   only called if some package's new dependencies are unknown
 ///
 /// # Returns
 ///
@@ -69,35 +119,61 @@ where
 pub fn detect_conflicts<F>(
     all_packages: &[Package],
     ignored_packages: &[String],
+    local: &[DbPackage],
     mut get_required_by: F,
 ) -> Vec<DependencyConflict>
 where
     F: FnMut(&str) -> Vec<String>,
 {
+    let local_records: HashMap<&str, &DbPackage> =
+        local.iter().map(|pkg| (pkg.name.as_str(), pkg)).collect();
     let ignored_set: HashSet<&str> = ignored_packages.iter().map(String::as_str).collect();
-    let updating_packages: HashSet<&str> = all_packages
+    let (ignored, updating): (Vec<&Package>, Vec<&Package>) = all_packages
+        .iter()
+        .partition(|p| ignored_set.contains(p.name.as_str()));
+    let unknown_depends: HashSet<&str> = updating
         .iter()
+        .filter(|p| p.depends.is_none())
         .map(|p| p.name.as_str())
-        .filter(|name| !ignored_set.contains(name))
         .collect();
 
     let mut conflicts = Vec::new();
 
-    for ignored in ignored_packages {
-        let required_by = get_required_by(ignored);
+    for ignored_name in ignored_packages {
+        let installed_version = ignored
+            .iter()
+            .find(|p| &p.name == ignored_name)
+            .and_then(|p| p.current_version.clone());
+
+        // What the installed version provides, its own name included
+        let mut provisions = ProvisionIndex::new();
+        if let Some(record) = local_records.get(ignored_name.as_str()) {
+            add_provisions(&mut provisions, record);
+        } else if let Some(installed) = installed_version.as_deref() {
+            provisions
+                .entry(ignored_name)
+                .or_default()
+                .push((ignored_name, Some(installed)));
+        }
+        let violations = find_violations(&updating, &provisions);
 
         // Find conflicts: packages that are being updated and require this ignored package
-        let mut conflicting: Vec<String> = required_by
-            .iter()
-            .filter(|dep| updating_packages.contains(dep.as_str()))
-            .cloned()
-            .collect();
+        let mut conflicting: Vec<String> = if unknown_depends.is_empty() {
+            Vec::new()
+        } else {
+            get_required_by(ignored_name)
+                .into_iter()
+                .filter(|dep| unknown_depends.contains(dep.as_str()))
+                .collect()
+        };
 
-        if !conflicting.is_empty() {
+        if !conflicting.is_empty() || !violations.is_empty() {
             conflicting.sort();
             conflicts.push(DependencyConflict {
-                ignored_package: ignored.clone(),
+                ignored_package: ignored_name.clone(),
+                installed_version,
                 required_by: conflicting,
+                violations,
             });
         }
     }
@@ -106,6 +182,406 @@ where
     conflicts
 }
 
+/// Computes every package that must be held back, transitively, to keep the
+/// ignored packages consistent with the rest of the update.
+///
+/// Repeats `detect_conflicts` with each round's dependents added to the
+/// ignored set until nothing new is flagged, so a dependent of a dependent is
+/// found too. Returns one tree per ignored package that holds anything back;
+/// a package reachable from several held packages appears once, under the
+/// one that flagged it first.
+pub fn hold_closure<F>(
+    all_packages: &[Package],
+    ignored_packages: &[String],
+    local: &[DbPackage],
+    mut get_required_by: F,
+) -> Vec<HoldNode>
+where
+    F: FnMut(&str) -> Vec<String>,
+{
+    let mut held: Vec<String> = ignored_packages.to_vec();
+    // Held package → packages it holds back, in discovery order
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+
+    loop {
+        let mut added = Vec::new();
+        for conflict in detect_conflicts(all_packages, &held, local, &mut get_required_by) {
+            let dependents = conflict
+                .violations
+                .iter()
+                .map(|v| &v.package)
+                .chain(&conflict.required_by);
+            for dependent in dependents {
+                if !held.contains(dependent) && !added.contains(dependent) {
+                    added.push(dependent.clone());
+                    children
+                        .entry(conflict.ignored_package.clone())
+                        .or_default()
+                        .push(dependent.clone());
+                }
+            }
+        }
+        if added.is_empty() {
+            break;
+        }
+        held.extend(added);
+    }
+
+    let mut roots: Vec<&String> = ignored_packages
+        .iter()
+        .filter(|name| children.contains_key(*name))
+        .collect();
+    roots.sort();
+    roots
+        .into_iter()
+        .map(|root| build_hold_node(root, &children))
+        .collect()
+}
+
+fn build_hold_node(package: &str, children: &HashMap<String, Vec<String>>) -> HoldNode {
+    let mut dependents: Vec<HoldNode> = children
+        .get(package)
+        .into_iter()
+        .flatten()
+        .map(|child| build_hold_node(child, children))
+        .collect();
+    dependents.sort_by(|a, b| a.package.cmp(&b.package));
+    HoldNode {
+        package: package.to_string(),
+        dependents,
+    }
+}
+
+/// Returns every package in the hold trees below their roots, in tree order.
+#[must_use]
+pub fn held_dependents(trees: &[HoldNode]) -> Vec<String> {
+    fn collect(node: &HoldNode, names: &mut Vec<String>) {
+        for dependent in &node.dependents {
+            names.push(dependent.package.clone());
+            collect(dependent, names);
+        }
+    }
+
+    let mut names = Vec::new();
+    for tree in trees {
+        collect(tree, &mut names);
+    }
+    names
+}
+
+/// Returns the dependencies of the new versions in `updating` that resolve to
+/// an ignored package's `provisions` but that none of them satisfy, sorted by
+/// package name. `libfoo.so=2-64` is violated if the ignored package only
+/// provides `libfoo.so=1-64`.
+fn find_violations(updating: &[&Package], provisions: &ProvisionIndex) -> Vec<ConstraintViolation> {
+    let mut violations: Vec<ConstraintViolation> = updating
+        .iter()
+        .filter_map(|p| p.depends.as_ref().map(|depends| (p, depends)))
+        .flat_map(|(p, depends)| {
+            depends
+                .iter()
+                .filter(|dep| {
+                    provisions.contains_key(dependency_name(dep))
+                        && find_provider(provisions, dep).is_none()
+                })
+                .map(|dep| ConstraintViolation {
+                    package: p.name.clone(),
+                    new_version: p.new_version.clone(),
+                    constraint: dep.clone(),
+                })
+        })
+        .collect();
+    violations.sort_by(|a, b| a.package.cmp(&b.package));
+    violations
+}
+
+/// Returns true if `version` satisfies the version constraint of `dep`.
+///
+/// `2.1-1` satisfies `foo>=2.0` but not `foo<2`; unversioned dependencies
+/// are satisfied by any version. Constraints without a pkgrel match any pkgrel.
+#[must_use]
+pub fn satisfies_dependency(version: &str, dep: &str) -> bool {
+    dep.find(['<', '>', '='])
+        .is_none_or(|idx| version::matches_spec(version, &dep[idx..]))
+}
+
+/// Detects packages that will lose a dependency they need once the updates are applied.
+///
+/// Compares what the packages being updated provide today (`local`) with what
+/// their new versions provide (`sync`), and checks the `depends` of every
+/// package staying behind: ignored packages and foreign packages (installed,
+/// but in no sync database) that are not being updated. A dependency breaks if
+/// an updated package satisfies it today and nothing satisfies it afterwards.
+///
+/// Updates without a sync record of their new version (e.g. AUR packages) are
+/// assumed to keep providing what they provide today.
+#[must_use]
+pub fn detect_library_breakage(
+    all_packages: &[Package],
+    ignored_packages: &[String],
+    local: &[DbPackage],
+    sync: &[DbPackage],
+) -> Vec<LibraryBreakage> {
+    let ignored_set: HashSet<&str> = ignored_packages.iter().map(String::as_str).collect();
+    let sync_names: HashSet<&str> = sync.iter().map(|db| db.name.as_str()).collect();
+    let sync_records: HashMap<(&str, &str), &DbPackage> = sync
+        .iter()
+        .map(|db| ((db.name.as_str(), db.version.as_str()), db))
+        .collect();
+
+    // New records of the packages being updated, by name
+    let updated: HashMap<&str, &DbPackage> = all_packages
+        .iter()
+        .filter(|p| !ignored_set.contains(p.name.as_str()))
+        .filter_map(|p| {
+            let record = sync_records.get(&(p.name.as_str(), p.new_version.as_str()))?;
+            Some((p.name.as_str(), *record))
+        })
+        .collect();
+    let pending: HashSet<&str> = all_packages.iter().map(|p| p.name.as_str()).collect();
+
+    let mut before = ProvisionIndex::new();
+    let mut after = ProvisionIndex::new();
+    for pkg in local {
+        if updated.contains_key(pkg.name.as_str()) {
+            add_provisions(&mut before, pkg);
+        } else {
+            add_provisions(&mut after, pkg);
+        }
+    }
+    for record in updated.values() {
+        add_provisions(&mut after, record);
+    }
+
+    let mut breakages = Vec::new();
+    let staying = local.iter().filter(|pkg| {
+        ignored_set.contains(pkg.name.as_str())
+            || (!sync_names.contains(pkg.name.as_str()) && !pending.contains(pkg.name.as_str()))
+    });
+    for pkg in staying {
+        for dep in &pkg.depends {
+            let Some(provider) = find_provider(&before, dep) else {
+                continue;
+            };
+            if find_provider(&after, dep).is_none() {
+                breakages.push(LibraryBreakage {
+                    package: pkg.name.clone(),
+                    dependency: dep.clone(),
+                    provider: provider.to_string(),
+                });
+            }
+        }
+    }
+
+    breakages.sort_by(|a, b| {
+        a.package
+            .cmp(&b.package)
+            .then_with(|| a.dependency.cmp(&b.dependency))
+    });
+    breakages
+}
+
+/// Adds a package's own name and its `provides` to a provision index.
+fn add_provisions<'a>(index: &mut ProvisionIndex<'a>, pkg: &'a DbPackage) {
+    index
+        .entry(&pkg.name)
+        .or_default()
+        .push((&pkg.name, Some(&pkg.version)));
+    for provide in &pkg.provides {
+        let version = provide.split_once('=').map(|(_, version)| version);
+        index
+            .entry(dependency_name(provide))
+            .or_default()
+            .push((&pkg.name, version));
+    }
+}
+
+/// Returns true if `pkg` satisfies `dep`, by name or through its `provides`.
+///
+/// `%CONFLICTS%` entries resolve the same way as dependencies.
+#[must_use]
+pub fn package_satisfies(pkg: &DbPackage, dep: &str) -> bool {
+    let mut provisions = ProvisionIndex::new();
+    add_provisions(&mut provisions, pkg);
+    find_provider(&provisions, dep).is_some()
+}
+
+/// Returns the first provider in `index` satisfying `dep`.
+///
+/// Like pacman, an unversioned provision only satisfies unversioned dependencies.
+fn find_provider<'a>(index: &ProvisionIndex<'a>, dep: &str) -> Option<&'a str> {
+    index
+        .get(dependency_name(dep))?
+        .iter()
+        .find(|(_, version)| match dep.find(['<', '>', '=']) {
+            None => true,
+            Some(_) => version.is_some_and(|version| satisfies_dependency(version, dep)),
+        })
+        .map(|(provider, _)| *provider)
+}
+
+/// Strips the version constraint from a dependency or provision string.
+///
+/// `glibc>=2.38` → `glibc`, `libc.so=6-64` → `libc.so`
+#[must_use]
+pub fn dependency_name(dep: &str) -> &str {
+    dep.find(['<', '>', '='])
+        .map_or(dep, |idx| &dep[..idx])
+        .trim()
+}
+
+/// Maps every installed package name, and every name installed packages
+/// provide, to the package behind it.
+///
+/// A package name always maps to itself; `sh` → `bash`, `libcrypto.so` →
+/// `openssl`. Names provided by several packages go to the first by name.
+#[must_use]
+pub fn build_provider_index(local: &[DbPackage]) -> HashMap<String, String> {
+    let mut sorted: Vec<&DbPackage> = local.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut index: HashMap<String, String> = sorted
+        .iter()
+        .map(|pkg| (pkg.name.clone(), pkg.name.clone()))
+        .collect();
+    for pkg in sorted {
+        for provide in &pkg.provides {
+            index
+                .entry(dependency_name(provide).to_string())
+                .or_insert_with(|| pkg.name.clone());
+        }
+    }
+    index
+}
+
+/// Builds a reverse dependency index from local database records.
+///
+/// Maps every installed package name to the sorted list of packages whose
+/// `depends` it satisfies, by name or through `provides` — the same relation
+/// pacman reports as "Required By". Packages nothing depends on map to an
+/// empty list, so a missing key means the package is not installed.
+#[must_use]
+pub fn build_required_by_index(local: &[DbPackage]) -> HashMap<String, Vec<String>> {
+    // Every name a dependency can resolve to, mapped to the packages providing it
+    let mut providers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for pkg in local {
+        providers.entry(&pkg.name).or_default().push(&pkg.name);
+        for provide in &pkg.provides {
+            providers
+                .entry(dependency_name(provide))
+                .or_default()
+                .push(&pkg.name);
+        }
+    }
+
+    let mut index: HashMap<String, Vec<String>> = local
+        .iter()
+        .map(|pkg| (pkg.name.clone(), Vec::new()))
+        .collect();
+
+    for pkg in local {
+        for dep in &pkg.depends {
+            for provider in providers.get(dependency_name(dep)).into_iter().flatten() {
+                if let Some(required_by) = index.get_mut(*provider)
+                    && !required_by.contains(&pkg.name)
+                {
+                    required_by.push(pkg.name.clone());
+                }
+            }
+        }
+    }
+
+    for required_by in index.values_mut() {
+        required_by.sort();
+    }
+    index
+}
+
+/// Results of every dependency check for one ignore list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyProblems {
+    pub conflicts: Vec<DependencyConflict>,
+    pub breakages: Vec<LibraryBreakage>,
+    /// Packages to hold back along with the ignored ones (`transitive_holds`)
+    pub hold_trees: Vec<HoldNode>,
+}
+
+impl DependencyProblems {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty() && self.breakages.is_empty()
+    }
+}
+
+/// Runs the conflict, library breakage and (with `transitive_holds`) hold
+/// closure checks for the given ignore list.
+///
+/// Reverse dependencies come from `local` when it is available; without it,
+/// `query_required_by` is asked instead and library breakage is skipped, since
+/// it needs both databases.
+///
+/// # Errors
+///
+/// Returns the warnings of reverse dependency lookups that failed.
+pub fn find_problems<F>(
+    all_packages: &[Package],
+    ignored_packages: &[String],
+    local: Option<&[DbPackage]>,
+    sync: &[DbPackage],
+    transitive_holds: bool,
+    mut query_required_by: F,
+) -> Result<DependencyProblems, Vec<String>>
+where
+    F: FnMut(&str) -> (Vec<String>, Option<String>),
+{
+    let breakages = local
+        .map(|local| detect_library_breakage(all_packages, ignored_packages, local, sync))
+        .unwrap_or_default();
+
+    let index = local
+        .filter(|_| !ignored_packages.is_empty())
+        .map(build_required_by_index);
+    let mut required_by = |pkg: &str| match &index {
+        Some(index) => match index.get(pkg) {
+            Some(deps) => (deps.clone(), None),
+            None => (Vec::new(), Some(format!("Package '{pkg}' not found"))),
+        },
+        None => query_required_by(pkg),
+    };
+
+    let local = local.unwrap_or_default();
+    let conflicts = check_conflicts(all_packages, ignored_packages, local, &mut required_by)?;
+    let hold_trees = if transitive_holds && !conflicts.is_empty() {
+        hold_closure(all_packages, ignored_packages, local, |pkg| {
+            required_by(pkg).0
+        })
+    } else {
+        Vec::new()
+    };
+
+    Ok(DependencyProblems {
+        conflicts,
+        breakages,
+        hold_trees,
+    })
+}
+
+/// Describes the result of re-running the dependency checks after a fix.
+///
+/// Remaining problems are only counted; the warning itself reappears once the
+/// update is started again.
+#[must_use]
+pub fn recheck_summary(result: &Result<DependencyProblems, Vec<String>>) -> String {
+    match result {
+        Ok(problems) if problems.is_empty() => "no dependency conflicts remain".to_string(),
+        Ok(problems) => format!(
+            "{} dependency conflict(s) remain",
+            problems.conflicts.len() + problems.breakages.len()
+        ),
+        Err(warnings) => format!("dependency check failed: {}", warnings.join("; ")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,15 +593,345 @@ mod tests {
             current_version: Some("1.0.0".to_string()),
             new_version: "2.0.0".to_string(),
             repository: PackageRepository::Official,
+            download_size: None,
+            installed_size_delta: None,
+            sync_repo: None,
+            groups: Vec::new(),
+            depends: None,
+        }
+    }
+
+    fn make_package_with_depends(name: &str, depends: &[&str]) -> Package {
+        Package {
+            depends: Some(depends.iter().map(ToString::to_string).collect()),
+            ..make_package(name)
+        }
+    }
+
+    fn make_ignored(name: &str, installed: &str) -> Package {
+        Package {
+            current_version: Some(installed.to_string()),
+            ..make_package(name)
+        }
+    }
+
+    fn make_db_package(name: &str, depends: &[&str], provides: &[&str]) -> DbPackage {
+        DbPackage {
+            name: name.to_string(),
+            version: "1.0-1".to_string(),
+            depends: depends.iter().map(ToString::to_string).collect(),
+            provides: provides.iter().map(ToString::to_string).collect(),
+            ..DbPackage::default()
         }
     }
 
+    #[test]
+    fn test_dependency_name_strips_constraint() {
+        assert_eq!(dependency_name("glibc>=2.38"), "glibc");
+        assert_eq!(dependency_name("libc.so=6-64"), "libc.so");
+        assert_eq!(dependency_name("bash"), "bash");
+    }
+
+    #[test]
+    fn test_satisfies_dependency() {
+        assert!(satisfies_dependency("2.1-1", "foo>=2.0"));
+        assert!(!satisfies_dependency("1.9-1", "foo>=2.0"));
+        assert!(satisfies_dependency("1.9-1", "foo<2"));
+        assert!(satisfies_dependency("2.0-3", "foo=2.0"));
+        assert!(!satisfies_dependency("2.0-3", "foo=2.0-1"));
+        assert!(satisfies_dependency("0.1-1", "foo"));
+    }
+
+    #[test]
+    fn test_versioned_constraint_violation() {
+        let packages = vec![
+            make_ignored("foo", "1.9-1"),
+            make_package_with_depends("bar", &["foo>=2.0", "glibc"]),
+            make_package_with_depends("baz", &["foo"]),
+        ];
+        let ignored = vec!["foo".to_string()];
+
+        let conflicts = detect_conflicts(&packages, &ignored, &[], |_| {
+            panic!("reverse dependencies are not needed when all depends are known")
+        });
+
+        assert_eq!(
+            conflicts,
+            vec![DependencyConflict {
+                ignored_package: "foo".to_string(),
+                installed_version: Some("1.9-1".to_string()),
+                required_by: Vec::new(),
+                violations: vec![ConstraintViolation {
+                    package: "bar".to_string(),
+                    new_version: "2.0.0".to_string(),
+                    constraint: "foo>=2.0".to_string(),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_violation_through_provides() {
+        let packages = vec![
+            make_ignored("foo", "1.0-1"),
+            make_package_with_depends("bar", &["libfoo.so=2-64"]),
+            make_package_with_depends("baz", &["libfoo.so=1-64"]),
+        ];
+        let ignored = vec!["foo".to_string()];
+        let local = vec![make_db_package("foo", &[], &["libfoo.so=1-64"])];
+
+        let conflicts = detect_conflicts(&packages, &ignored, &local, |_| Vec::new());
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].violations,
+            vec![ConstraintViolation {
+                package: "bar".to_string(),
+                new_version: "2.0.0".to_string(),
+                constraint: "libfoo.so=2-64".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_satisfied_constraint_is_not_a_conflict() {
+        let packages = vec![
+            make_ignored("foo", "2.1-1"),
+            make_package_with_depends("bar", &["foo>=2.0"]),
+        ];
+        let ignored = vec!["foo".to_string()];
+
+        let conflicts = detect_conflicts(&packages, &ignored, &[], |_| vec!["bar".to_string()]);
+
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_depends_fall_back_to_required_by() {
+        let packages = vec![
+            make_ignored("foo", "2.1-1"),
+            make_package_with_depends("bar", &["foo>=2.0"]),
+            make_package("aur-tool"),
+        ];
+        let ignored = vec!["foo".to_string()];
+
+        let conflicts = detect_conflicts(&packages, &ignored, &[], |_| {
+            vec!["aur-tool".to_string(), "bar".to_string()]
+        });
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].required_by, vec!["aur-tool"]);
+        assert!(conflicts[0].violations.is_empty());
+    }
+
+    fn make_update(name: &str, new_version: &str) -> Package {
+        Package {
+            new_version: new_version.to_string(),
+            ..make_package(name)
+        }
+    }
+
+    fn make_versioned_db_package(
+        name: &str,
+        version: &str,
+        depends: &[&str],
+        provides: &[&str],
+    ) -> DbPackage {
+        DbPackage {
+            version: version.to_string(),
+            ..make_db_package(name, depends, provides)
+        }
+    }
+
+    #[test]
+    fn test_soname_bump_breaks_foreign_package() {
+        let local = vec![
+            make_versioned_db_package("icu", "74.2-1", &[], &["libicuuc.so=74-64"]),
+            make_versioned_db_package("aur-tool", "1.0-1", &["libicuuc.so=74-64"], &[]),
+            make_versioned_db_package("libxml2", "2.12-1", &["libicuuc.so=74-64"], &[]),
+        ];
+        let sync = vec![
+            make_versioned_db_package("icu", "75.1-1", &[], &["libicuuc.so=75-64"]),
+            make_versioned_db_package("libxml2", "2.12-1", &["libicuuc.so=74-64"], &[]),
+        ];
+        let packages = vec![make_update("icu", "75.1-1")];
+
+        let breakages = detect_library_breakage(&packages, &[], &local, &sync);
+
+        // libxml2 is in a sync database and up to date, so it is not staying behind
+        assert_eq!(
+            breakages,
+            vec![LibraryBreakage {
+                package: "aur-tool".to_string(),
+                dependency: "libicuuc.so=74-64".to_string(),
+                provider: "icu".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_soname_bump_breaks_held_package() {
+        let local = vec![
+            make_versioned_db_package("icu", "74.2-1", &[], &["libicuuc.so=74-64"]),
+            make_versioned_db_package("libxml2", "2.12-1", &["libicuuc.so=74-64"], &[]),
+        ];
+        let sync = vec![
+            make_versioned_db_package("icu", "75.1-1", &[], &["libicuuc.so=75-64"]),
+            make_versioned_db_package("libxml2", "2.13-1", &["libicuuc.so=75-64"], &[]),
+        ];
+        let packages = vec![
+            make_update("icu", "75.1-1"),
+            make_update("libxml2", "2.13-1"),
+        ];
+        let ignored = vec!["libxml2".to_string()];
+
+        let breakages = detect_library_breakage(&packages, &ignored, &local, &sync);
+
+        assert_eq!(breakages.len(), 1);
+        assert_eq!(breakages[0].package, "libxml2");
+        assert_eq!(breakages[0].dependency, "libicuuc.so=74-64");
+    }
+
+    #[test]
+    fn test_kept_provision_is_not_a_breakage() {
+        let local = vec![
+            make_versioned_db_package("icu", "74.2-1", &[], &["libicuuc.so=74-64"]),
+            make_versioned_db_package("icu74", "74.2-1", &[], &["libicuuc.so=74-64"]),
+            make_versioned_db_package("aur-tool", "1.0-1", &["libicuuc.so=74-64", "icu"], &[]),
+        ];
+        let sync = vec![make_versioned_db_package(
+            "icu",
+            "75.1-1",
+            &[],
+            &["libicuuc.so=75-64"],
+        )];
+        let packages = vec![make_update("icu", "75.1-1")];
+
+        // icu74 (foreign, not updated) keeps providing the old soname
+        assert!(detect_library_breakage(&packages, &[], &local, &sync).is_empty());
+    }
+
+    #[test]
+    fn test_ignored_update_keeps_its_provisions() {
+        let local = vec![
+            make_versioned_db_package("icu", "74.2-1", &[], &["libicuuc.so=74-64"]),
+            make_versioned_db_package("aur-tool", "1.0-1", &["libicuuc.so=74-64"], &[]),
+        ];
+        let sync = vec![make_versioned_db_package(
+            "icu",
+            "75.1-1",
+            &[],
+            &["libicuuc.so=75-64"],
+        )];
+        let packages = vec![make_update("icu", "75.1-1")];
+        let ignored = vec!["icu".to_string()];
+
+        assert!(detect_library_breakage(&packages, &ignored, &local, &sync).is_empty());
+    }
+
+    #[test]
+    fn test_hold_closure_follows_dependents_of_dependents() {
+        let packages = vec![
+            make_ignored("qt6-base", "6.7.0-1"),
+            make_package_with_depends("qt6-declarative", &["qt6-base>=6.8.0"]),
+            make_package_with_depends("kirigami", &["qt6-declarative>=2.0"]),
+            make_package("aur-qt-app"),
+            make_package_with_depends("unrelated", &["glibc"]),
+        ];
+        let ignored = vec!["qt6-base".to_string()];
+
+        let trees = hold_closure(&packages, &ignored, &[], |name| match name {
+            "qt6-base" => vec!["aur-qt-app".to_string(), "qt6-declarative".to_string()],
+            _ => Vec::new(),
+        });
+
+        assert_eq!(
+            trees,
+            vec![HoldNode {
+                package: "qt6-base".to_string(),
+                dependents: vec![
+                    HoldNode {
+                        package: "aur-qt-app".to_string(),
+                        dependents: Vec::new(),
+                    },
+                    HoldNode {
+                        package: "qt6-declarative".to_string(),
+                        dependents: vec![HoldNode {
+                            package: "kirigami".to_string(),
+                            dependents: Vec::new(),
+                        }],
+                    },
+                ],
+            }]
+        );
+        assert_eq!(
+            held_dependents(&trees),
+            vec!["aur-qt-app", "qt6-declarative", "kirigami"]
+        );
+    }
+
+    #[test]
+    fn test_hold_closure_lists_shared_dependents_once() {
+        let packages = vec![
+            make_ignored("a", "1.0-1"),
+            make_ignored("b", "1.0-1"),
+            make_package_with_depends("c", &["a>=2", "b>=2"]),
+        ];
+        let ignored = vec!["a".to_string(), "b".to_string()];
+
+        let trees = hold_closure(&packages, &ignored, &[], |_| Vec::new());
+
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].package, "a");
+        assert_eq!(held_dependents(&trees), vec!["c"]);
+    }
+
+    #[test]
+    fn test_required_by_index_by_name_and_provides() {
+        let local = vec![
+            make_db_package("glibc", &[], &["libc.so=6-64"]),
+            make_db_package("bash", &["glibc", "readline>=8.0"], &["sh"]),
+            make_db_package("coreutils", &["libc.so=6-64", "glibc"], &[]),
+            make_db_package("readline", &["glibc"], &[]),
+            make_db_package("base", &["sh"], &[]),
+        ];
+
+        let index = build_required_by_index(&local);
+
+        assert_eq!(index["glibc"], vec!["bash", "coreutils", "readline"]);
+        assert_eq!(index["readline"], vec!["bash"]);
+        assert_eq!(index["bash"], vec!["base"]);
+        assert!(index["base"].is_empty());
+        assert!(!index.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_provider_index_resolves_provisions() {
+        let local = vec![
+            make_db_package("zsh", &[], &["sh"]),
+            make_db_package("bash", &[], &["sh"]),
+            make_db_package("openssl", &[], &["libcrypto.so=3-64"]),
+            make_db_package("busybox", &[], &["bash"]),
+        ];
+
+        let index = build_provider_index(&local);
+
+        assert_eq!(index.get("sh").map(String::as_str), Some("bash"));
+        assert_eq!(
+            index.get("libcrypto.so").map(String::as_str),
+            Some("openssl")
+        );
+        // Real packages win over provisions of the same name
+        assert_eq!(index.get("bash").map(String::as_str), Some("bash"));
+        assert_eq!(index.get("glibc"), None);
+    }
+
     #[test]
     fn test_no_conflicts() {
         let packages = vec![make_package("pkg1"), make_package("pkg2")];
         let ignored = vec!["pkg3".to_string()];
 
-        let conflicts = detect_conflicts(&packages, &ignored, |_| Vec::new());
+        let conflicts = detect_conflicts(&packages, &ignored, &[], |_| Vec::new());
 
         assert!(conflicts.is_empty());
     }
@@ -146,7 +952,7 @@ mod tests {
             }
         };
 
-        let conflicts = detect_conflicts(&packages, &ignored, get_deps);
+        let conflicts = detect_conflicts(&packages, &ignored, &[], get_deps);
 
         assert_eq!(conflicts.len(), 1);
         assert_eq!(conflicts[0].ignored_package, "pkg2");
@@ -167,7 +973,7 @@ mod tests {
             }
         };
 
-        let conflicts = detect_conflicts(&packages, &ignored, get_deps);
+        let conflicts = detect_conflicts(&packages, &ignored, &[], get_deps);
 
         assert!(conflicts.is_empty());
     }
@@ -189,7 +995,7 @@ mod tests {
             }
         };
 
-        let conflicts = detect_conflicts(&packages, &ignored, get_deps);
+        let conflicts = detect_conflicts(&packages, &ignored, &[], get_deps);
 
         assert_eq!(conflicts.len(), 2);
     }
@@ -207,7 +1013,7 @@ mod tests {
             }
         };
 
-        let result = check_conflicts(&packages, &ignored, get_required_by);
+        let result = check_conflicts(&packages, &ignored, &[], get_required_by);
 
         assert!(result.is_err());
         if let Err(warnings) = result {
@@ -228,7 +1034,7 @@ mod tests {
             }
         };
 
-        let result = check_conflicts(&packages, &ignored, get_required_by);
+        let result = check_conflicts(&packages, &ignored, &[], get_required_by);
 
         assert!(result.is_ok());
         if let Ok(conflicts) = result {
@@ -245,11 +1051,74 @@ mod tests {
 
         let get_required_by = |_: &str| -> (Vec<String>, Option<String>) { (vec![], None) };
 
-        let result = check_conflicts(&packages, &ignored, get_required_by);
+        let result = check_conflicts(&packages, &ignored, &[], get_required_by);
 
         assert!(result.is_ok());
         if let Ok(conflicts) = result {
             assert!(conflicts.is_empty());
         }
     }
+
+    #[test]
+    fn test_find_problems_uses_local_index() {
+        let packages = vec![make_ignored("foo", "1.0-1"), make_package("aur-tool")];
+        let ignored = vec!["foo".to_string()];
+        let local = vec![
+            make_db_package("foo", &[], &[]),
+            make_db_package("aur-tool", &["foo"], &[]),
+        ];
+
+        let Ok(problems) = find_problems(&packages, &ignored, Some(&local), &[], true, |_| {
+            panic!("the local database answers reverse dependencies")
+        }) else {
+            panic!("expected the check to succeed");
+        };
+
+        assert_eq!(problems.conflicts.len(), 1);
+        assert_eq!(problems.conflicts[0].required_by, vec!["aur-tool"]);
+        assert_eq!(held_dependents(&problems.hold_trees), vec!["aur-tool"]);
+        assert!(problems.breakages.is_empty());
+    }
+
+    #[test]
+    fn test_find_problems_without_local_queries_resolver() {
+        let packages = vec![make_ignored("foo", "1.0-1"), make_package("aur-tool")];
+        let ignored = vec!["foo".to_string()];
+
+        let Ok(problems) = find_problems(&packages, &ignored, None, &[], false, |_| {
+            (vec!["aur-tool".to_string()], None)
+        }) else {
+            panic!("expected the check to succeed");
+        };
+
+        assert_eq!(problems.conflicts[0].required_by, vec!["aur-tool"]);
+        assert!(problems.hold_trees.is_empty());
+
+        let result = find_problems(&packages, &ignored, None, &[], false, |_| {
+            (Vec::new(), Some("timed out".to_string()))
+        });
+        assert_eq!(
+            recheck_summary(&result),
+            "dependency check failed: Failed to check dependencies for foo: timed out"
+        );
+    }
+
+    #[test]
+    fn test_recheck_summary_counts_remaining_problems() {
+        let mut problems = DependencyProblems::default();
+        assert_eq!(
+            recheck_summary(&Ok(problems.clone())),
+            "no dependency conflicts remain"
+        );
+
+        problems.breakages.push(LibraryBreakage {
+            package: "aur-tool".to_string(),
+            dependency: "libicuuc.so=74-64".to_string(),
+            provider: "icu".to_string(),
+        });
+        assert_eq!(
+            recheck_summary(&Ok(problems)),
+            "1 dependency conflict(s) remain"
+        );
+    }
 }
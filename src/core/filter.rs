@@ -1,19 +1,200 @@
-use crate::models::config::Config;
-use crate::models::package::Package;
+use crate::core::version;
+use crate::models::config::{AutoIgnoreRule, Config, HoldRule};
+use crate::models::package::{Package, PackageRepository, UpdateKind};
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Prefix marking a permanent exclusion as a regular expression (`re:^python-.*-bin$`).
+pub const REGEX_PREFIX: &str = "re:";
 
 /// Applies permanent package exclusions from configuration.
 ///
+/// Entries may be package names, globs or `re:` regexes (see `rule_matches`).
 /// Returns a new vector with excluded packages filtered out.
 #[must_use]
 #[allow(dead_code)]
 pub fn apply_permanent_excludes(packages: Vec<Package>, config: &Config) -> Vec<Package> {
     let excludes = &config.exclude.permanent;
+    let mut patterns = PatternCache::default();
+    patterns.compile(excludes.iter().map(AsRef::as_ref));
     packages
         .into_iter()
-        .filter(|pkg| !excludes.contains(&pkg.name))
+        .filter(|pkg| matching_rule(&pkg.name, excludes, &patterns).is_none())
+        .collect()
+}
+
+/// Returns the permanent exclusion rule that covers `name`, if any.
+///
+/// An exact name entry wins over patterns; otherwise the first matching
+/// pattern in configuration order is returned.
+#[must_use]
+pub fn matching_rule<'a, R: AsRef<str>>(
+    name: &str,
+    rules: &'a [R],
+    patterns: &PatternCache,
+) -> Option<&'a str> {
+    rules
+        .iter()
+        .map(AsRef::as_ref)
+        .find(|rule| *rule == name)
+        .or_else(|| {
+            rules
+                .iter()
+                .map(AsRef::as_ref)
+                .find(|rule| is_pattern(rule) && patterns.matches(rule, name))
+        })
+}
+
+/// Returns the first hold that keeps `package` back at its pending version.
+#[must_use]
+pub fn matching_hold<'a>(
+    package: &Package,
+    holds: &'a [HoldRule],
+    patterns: &PatternCache,
+) -> Option<&'a HoldRule> {
+    holds.iter().find(|hold| {
+        patterns.matches(&hold.name, &package.name)
+            && version::matches_spec(&package.new_version, &hold.version)
+    })
+}
+
+/// Returns true if `rule` is a glob or regex pattern rather than a package name.
+#[must_use]
+pub fn is_pattern(rule: &str) -> bool {
+    rule.starts_with(REGEX_PREFIX) || rule.contains(['*', '?'])
+}
+
+/// Returns true if `rule` covers the package `name`.
+///
+/// - `re:<regex>`: regex search, so anchor with `^`/`$` to match whole names
+/// - containing `*` or `?`: glob over the whole name (`*-git`, `nvidia-*`)
+/// - anything else: exact package name
+///
+/// Invalid regexes match nothing (see `invalid_patterns`). Compiles `rule`
+/// on every call; use `NamePattern` or `PatternCache` to match many names.
+#[must_use]
+pub fn rule_matches(rule: &str, name: &str) -> bool {
+    NamePattern::new(rule).matches(name)
+}
+
+/// A rule compiled once for matching many names (see `rule_matches`).
+#[derive(Debug, Clone)]
+pub enum NamePattern {
+    Exact(String),
+    Glob(String),
+    Regex(Regex),
+    /// A `re:` rule whose regex does not compile; matches nothing
+    Invalid,
+}
+
+impl NamePattern {
+    #[must_use]
+    pub fn new(rule: &str) -> Self {
+        if let Some(pattern) = rule.strip_prefix(REGEX_PREFIX) {
+            Regex::new(pattern).map_or(Self::Invalid, Self::Regex)
+        } else if is_pattern(rule) {
+            Self::Glob(rule.to_string())
+        } else {
+            Self::Exact(rule.to_string())
+        }
+    }
+
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Exact(rule) => rule == name,
+            Self::Glob(rule) => glob_matches(rule.as_bytes(), name.as_bytes()),
+            Self::Regex(re) => re.is_match(name),
+            Self::Invalid => false,
+        }
+    }
+}
+
+/// Rules compiled when they are loaded, keyed by rule text.
+///
+/// Rules missing from the cache are compiled on the fly, so a rule added
+/// without `compile` is slower to match but never matched wrongly.
+#[derive(Debug, Clone, Default)]
+pub struct PatternCache {
+    patterns: HashMap<String, NamePattern>,
+}
+
+impl PatternCache {
+    /// Compiles the rules that are not cached yet.
+    pub fn compile<'a>(&mut self, rules: impl IntoIterator<Item = &'a str>) {
+        for rule in rules {
+            if !self.patterns.contains_key(rule) {
+                self.patterns
+                    .insert(rule.to_string(), NamePattern::new(rule));
+            }
+        }
+    }
+
+    /// Returns true if `rule` covers the package `name` (see `rule_matches`).
+    #[must_use]
+    pub fn matches(&self, rule: &str, name: &str) -> bool {
+        self.patterns
+            .get(rule)
+            .map_or_else(|| rule_matches(rule, name), |pattern| pattern.matches(name))
+    }
+}
+
+/// Returns the `re:` rules whose regex does not compile, with the error.
+#[must_use]
+pub fn invalid_patterns<R: AsRef<str>>(rules: &[R]) -> Vec<(String, String)> {
+    rules
+        .iter()
+        .map(AsRef::as_ref)
+        .filter_map(|rule| {
+            let pattern = rule.strip_prefix(REGEX_PREFIX)?;
+            Regex::new(pattern)
+                .err()
+                .map(|e| (rule.to_string(), e.to_string()))
+        })
         .collect()
 }
 
+/// Returns true if the glob `pattern` covers the whole of `name`.
+///
+/// Only `*` and `?` are special, so a pattern without them matches its exact
+/// name and `re:` means nothing. This is how pacman.conf's `IgnorePkg` and
+/// `Include` entries match.
+#[must_use]
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    glob_matches(pattern.as_bytes(), name.as_bytes())
+}
+
+/// Matches `*` (any run) and `?` (any single byte) against the whole name.
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it is retried from
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            },
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            },
+            _ => match backtrack {
+                // Let the last `*` swallow one more byte and retry
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                },
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
 /// Applies temporary package exclusions for the current run.
 ///
 /// Returns a new vector with excluded packages filtered out.
@@ -25,3 +206,15 @@ pub fn apply_temporary_excludes(packages: Vec<Package>, temp_excludes: &[String]
         .filter(|pkg| !temp_excludes.contains(&pkg.name))
         .collect()
 }
+
+/// Returns true if an update of `kind` from `repository` matches any auto-ignore rule.
+#[must_use]
+pub fn matches_auto_ignore(
+    kind: UpdateKind,
+    repository: PackageRepository,
+    rules: &[AutoIgnoreRule],
+) -> bool {
+    rules
+        .iter()
+        .any(|rule| rule.kind == kind && rule.repository.is_none_or(|r| r == repository))
+}
@@ -1,3 +1,10 @@
 pub mod dependency;
 pub mod filter;
+pub mod origin;
 pub mod planner;
+pub mod preflight;
+pub mod size;
+pub mod snooze;
+pub mod time;
+pub mod transaction;
+pub mod version;
@@ -0,0 +1,35 @@
+use crate::models::package::{DbPackage, Package, PackageRepository};
+use std::collections::HashMap;
+
+/// Fills in the sync repository, groups and dependencies of each package from
+/// sync database records.
+///
+/// Records matching the pending `new_version` are preferred; otherwise the first
+/// record with the same name is used, in the order the databases were read.
+/// Dependencies are only taken from a record of the pending version, since
+/// another version's may differ. AUR packages, which may share a name with a
+/// repository package, and packages without any record are left as is.
+pub fn annotate_origins(packages: &mut [Package], sync: &[DbPackage]) {
+    let mut by_name: HashMap<&str, &DbPackage> = HashMap::new();
+    let mut by_version: HashMap<(&str, &str), &DbPackage> = HashMap::new();
+    for db in sync {
+        by_name.entry(db.name.as_str()).or_insert(db);
+        by_version
+            .entry((db.name.as_str(), db.version.as_str()))
+            .or_insert(db);
+    }
+
+    for package in packages {
+        if package.repository == PackageRepository::Aur {
+            continue;
+        }
+        let exact = by_version.get(&(package.name.as_str(), package.new_version.as_str()));
+        if let Some(db) = exact {
+            package.depends = Some(db.depends.clone());
+        }
+        if let Some(db) = exact.or_else(|| by_name.get(package.name.as_str())) {
+            package.sync_repo.clone_from(&db.repository);
+            package.groups.clone_from(&db.groups);
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use crate::models::config::Config;
+use crate::models::config::{AurHelper, Config};
 use crate::models::package::{Package, PackageRepository};
+use std::path::Path;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UpdateMode {
@@ -9,30 +10,106 @@ pub enum UpdateMode {
 
 pub struct UpdatePlan {
     pub mode: UpdateMode,
-    #[allow(dead_code)]
     pub packages: Vec<Package>,
     pub ignore_list: Vec<String>,
+    /// Package groups passed as `--ignoregroup`
+    pub ignore_groups: Vec<String>,
 }
 
 impl UpdatePlan {
     /// Builds the command-line arguments for the update.
     ///
     /// Returns a vector of command parts including the program name and all arguments.
+    /// `EntireSystem` uses the configured AUR helper, falling back to `paru`.
     #[must_use]
     pub fn build_command(&self, config: &Config) -> Vec<String> {
+        let helper = config.helper.name.unwrap_or_default();
+
+        if self.mode == UpdateMode::EntireSystem && helper == AurHelper::Aurutils {
+            return self.build_aurutils_command(config);
+        }
+
         let mut cmd = match self.mode {
-            UpdateMode::EntireSystem => vec!["paru".to_string(), "-Syu".to_string()],
+            UpdateMode::EntireSystem => {
+                vec![helper.program().to_string(), "-Syu".to_string()]
+            },
             UpdateMode::OfficialOnly => {
                 vec!["sudo".to_string(), "pacman".to_string(), "-Syu".to_string()]
             },
         };
 
+        cmd.extend(self.ignore_args());
+        cmd.extend(config.behavior.extra_args.iter().cloned());
+        cmd
+    }
+
+    /// aurutils only builds AUR packages into a local repository, so the
+    /// entire-system update is `aur sync -u` followed by `pacman -Syu`.
+    /// `aur sync` knows no groups, so the AUR members of ignored groups are
+    /// added to its `--ignore` list and `--ignoregroup` only goes to pacman.
+    fn build_aurutils_command(&self, config: &Config) -> Vec<String> {
+        let mut sync_ignores = self.ignore_list.clone();
+        for package in &self.packages {
+            if package.repository == PackageRepository::Aur
+                && package
+                    .groups
+                    .iter()
+                    .any(|group| self.ignore_groups.contains(group))
+                && !sync_ignores.contains(&package.name)
+            {
+                sync_ignores.push(package.name.clone());
+            }
+        }
+
+        let mut sync = vec!["aur".to_string(), "sync".to_string(), "-u".to_string()];
+        if !sync_ignores.is_empty() {
+            sync.extend(["--ignore".to_string(), sync_ignores.join(",")]);
+        }
+
+        let mut upgrade = vec!["sudo".to_string(), "pacman".to_string(), "-Syu".to_string()];
+        upgrade.extend(self.ignore_args());
+        upgrade.extend(config.behavior.extra_args.iter().cloned());
+
+        let script = format!("{} && {}", shell_join(&sync), shell_join(&upgrade));
+        vec!["sh".to_string(), "-c".to_string(), script]
+    }
+
+    fn ignore_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
         if !self.ignore_list.is_empty() {
-            cmd.push("--ignore".to_string());
-            cmd.push(self.ignore_list.join(","));
+            args.extend(["--ignore".to_string(), self.ignore_list.join(",")]);
         }
+        if !self.ignore_groups.is_empty() {
+            args.extend(["--ignoregroup".to_string(), self.ignore_groups.join(",")]);
+        }
+        args
+    }
 
-        cmd.extend(config.behavior.extra_args.iter().cloned());
+    /// Builds a `pacman -Syu --print` dry run of the repository part of the update.
+    ///
+    /// `dbpath` must be a scratch copy of the databases whose `local` links to
+    /// the real one: `-y` refreshes its sync databases under `fakeroot`, as
+    /// `checkupdates` does, so the dry run sees what the real `-Syu` would
+    /// without root or touching the system databases. `--noconfirm` takes the
+    /// default answer to replacement prompts, as the real update would when
+    /// accepted.
+    #[must_use]
+    pub fn simulate_command(&self, dbpath: &Path) -> Vec<String> {
+        let mut cmd = vec![
+            "fakeroot".to_string(),
+            "--".to_string(),
+            "pacman".to_string(),
+            "-Syu".to_string(),
+            "--print".to_string(),
+            "--print-format".to_string(),
+            "%n %v %r %s".to_string(),
+            "--noconfirm".to_string(),
+            "--dbpath".to_string(),
+            dbpath.to_string_lossy().to_string(),
+            "--logfile".to_string(),
+            "/dev/null".to_string(),
+        ];
+        cmd.extend(self.ignore_args());
         cmd
     }
 
@@ -55,6 +132,40 @@ impl UpdatePlan {
     }
 }
 
+/// Builds the package cache cleanup offered when disk space is short.
+///
+/// Keeps only the most recent version of each package in `cachedir`.
+#[must_use]
+pub fn cache_cleanup_command(cachedir: &Path) -> Vec<String> {
+    vec![
+        "sudo".to_string(),
+        "paccache".to_string(),
+        "-r".to_string(),
+        "-k".to_string(),
+        "1".to_string(),
+        "-c".to_string(),
+        cachedir.to_string_lossy().to_string(),
+    ]
+}
+
+/// Joins arguments into a single shell command line, quoting where needed.
+fn shell_join(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let is_plain = !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.,=+@/:".contains(c));
+            if is_plain {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Creates an update plan with the specified mode and exclusions.
 ///
 /// For `OfficialOnly` mode, AUR packages are automatically filtered out.
@@ -76,5 +187,6 @@ pub fn create_plan(
         mode,
         packages,
         ignore_list: excluded,
+        ignore_groups: Vec::new(),
     }
 }
@@ -0,0 +1,112 @@
+use crate::models::disk::{FilesystemSpace, SpaceShortage};
+use crate::models::package::Package;
+use std::collections::HashSet;
+
+/// Extra room required on top of the package sizes, for hooks such as
+/// initramfs regeneration that write outside the package payload.
+pub const SPACE_MARGIN: u64 = 100 * 1024 * 1024;
+
+/// Outcome of the free disk space check before an update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpaceVerdict {
+    /// Both filesystems can hold the update
+    Sufficient,
+    /// Free space could not be determined; the update proceeds with this warning
+    Unknown(String),
+    /// Space is short; the user decides whether to clean the cache, proceed or cancel
+    Short(Vec<SpaceShortage>),
+}
+
+/// Decides whether the update fits, given the `df` results for the package
+/// cache and the root filesystem, in that order.
+///
+/// Called again after the cache was cleaned, with fresh `df` results.
+#[must_use]
+pub fn evaluate_free_space(
+    download: u64,
+    installed_delta: i64,
+    spaces: &[FilesystemSpace],
+) -> SpaceVerdict {
+    let [cache, root] = spaces else {
+        return SpaceVerdict::Unknown("unexpected df output".to_string());
+    };
+
+    let shortages = check_disk_space(download, installed_delta, cache, root);
+    if shortages.is_empty() {
+        SpaceVerdict::Sufficient
+    } else {
+        SpaceVerdict::Short(shortages)
+    }
+}
+
+/// Sums the download sizes of the packages not yet in the package cache.
+///
+/// `cache_files` are the file names in the cache directory. A package counts
+/// as downloaded when `<name>-<new_version>-<arch>.pkg.tar*` is among them;
+/// signature files do not count.
+#[must_use]
+pub fn uncached_download<'a, I>(packages: I, cache_files: &[String]) -> u64
+where
+    I: IntoIterator<Item = &'a Package>,
+{
+    let cached: HashSet<&str> = cache_files
+        .iter()
+        .filter_map(|file| {
+            let (stem, compression) = file.split_once(".pkg.tar")?;
+            if compression.contains(".sig") {
+                return None;
+            }
+            // Drop the architecture, leaving `<name>-<pkgver>-<pkgrel>`
+            Some(stem.rsplit_once('-')?.0)
+        })
+        .collect();
+
+    packages
+        .into_iter()
+        .filter(|package| {
+            !cached.contains(format!("{}-{}", package.name, package.new_version).as_str())
+        })
+        .filter_map(|package| package.download_size)
+        .sum()
+}
+
+/// Checks whether the package cache and root filesystem can hold the update.
+///
+/// The cache needs room for the downloads and the root filesystem for the
+/// installed-size growth. When both live on the same filesystem the two
+/// requirements are added up. Each non-zero requirement includes `SPACE_MARGIN`.
+#[must_use]
+pub fn check_disk_space(
+    download: u64,
+    installed_delta: i64,
+    cache: &FilesystemSpace,
+    root: &FilesystemSpace,
+) -> Vec<SpaceShortage> {
+    let growth = u64::try_from(installed_delta).unwrap_or(0);
+
+    let requirements = if cache.mount_point == root.mount_point {
+        vec![(
+            cache,
+            "package cache and root filesystem",
+            download + growth,
+        )]
+    } else {
+        vec![
+            (cache, "package cache", download),
+            (root, "root filesystem", growth),
+        ]
+    };
+
+    requirements
+        .into_iter()
+        .filter(|&(_, _, required)| required > 0)
+        .map(|(fs, purpose, required)| (fs, purpose, required + SPACE_MARGIN))
+        .filter(|&(fs, _, required)| fs.available < required)
+        .map(|(fs, purpose, required)| SpaceShortage {
+            mount_point: fs.mount_point.clone(),
+            purpose: purpose.to_string(),
+            required,
+            available: fs.available,
+        })
+        .collect()
+}
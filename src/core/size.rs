@@ -0,0 +1,60 @@
+use crate::models::package::{DbPackage, Package, PackageRepository};
+use std::collections::HashMap;
+
+/// Fills in download sizes and installed-size changes from database records.
+///
+/// `sync` holds the records of the repositories the update comes from and
+/// `local` those of the installed packages. A sync record only counts when its
+/// version matches the pending `new_version`, so a stale database never
+/// attributes the wrong sizes. AUR packages and packages without a matching
+/// record are left as is.
+pub fn annotate_sizes(packages: &mut [Package], sync: &[DbPackage], local: &[DbPackage]) {
+    let sync_index: HashMap<(&str, &str), &DbPackage> = sync
+        .iter()
+        .map(|db| ((db.name.as_str(), db.version.as_str()), db))
+        .collect();
+    let local_index: HashMap<&str, &DbPackage> =
+        local.iter().map(|db| (db.name.as_str(), db)).collect();
+
+    for package in packages {
+        if package.repository == PackageRepository::Aur {
+            continue;
+        }
+        let Some(new) = sync_index.get(&(package.name.as_str(), package.new_version.as_str()))
+        else {
+            continue;
+        };
+        package.download_size = new.download_size;
+
+        let current_size = local_index
+            .get(package.name.as_str())
+            .and_then(|db| db.installed_size);
+        package.installed_size_delta = match (new.installed_size, current_size) {
+            (Some(new_size), Some(current_size)) => size_delta(new_size, current_size),
+            _ => None,
+        };
+    }
+}
+
+/// Returns `new - current` in bytes.
+fn size_delta(new: u64, current: u64) -> Option<i64> {
+    Some(i64::try_from(new).ok()? - i64::try_from(current).ok()?)
+}
+
+/// Sums the sizes of the packages that will actually be upgraded.
+///
+/// Returns `(download_bytes, installed_delta_bytes, packages_without_sizes)`.
+#[must_use]
+pub fn size_totals<'a, I>(packages: I) -> (u64, i64, usize)
+where
+    I: IntoIterator<Item = &'a Package>,
+{
+    packages
+        .into_iter()
+        .fold((0, 0, 0), |(download, delta, unknown), package| {
+            match (package.download_size, package.installed_size_delta) {
+                (Some(size), Some(change)) => (download + size, delta + change, unknown),
+                _ => (download, delta, unknown + 1),
+            }
+        })
+}
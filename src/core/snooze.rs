@@ -0,0 +1,20 @@
+use crate::core::time::{self, SECONDS_PER_DAY};
+use crate::models::config::SnoozeRule;
+
+/// Splits snoozes into `(active, expired)` at `now` (Unix seconds).
+///
+/// Snoozes with an unparsable `until` count as expired so they get surfaced
+/// and pruned rather than ignoring a package forever.
+#[must_use]
+pub fn partition_snoozes(snoozes: &[SnoozeRule], now: u64) -> (Vec<SnoozeRule>, Vec<SnoozeRule>) {
+    snoozes
+        .iter()
+        .cloned()
+        .partition(|snooze| time::parse_timestamp(&snooze.until).is_some_and(|until| until > now))
+}
+
+/// Returns the expiry timestamp `days` days after `now`.
+#[must_use]
+pub fn expiry_after(now: u64, days: u64) -> String {
+    time::format_timestamp(now.saturating_add(days.saturating_mul(SECONDS_PER_DAY)))
+}
@@ -0,0 +1,111 @@
+/// Seconds in a UTC day (leap seconds are ignored, as in Unix time).
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Formats Unix seconds as an RFC 3339 UTC timestamp (`2026-10-24T09:30:00Z`).
+#[must_use]
+pub fn format_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil_from_days(secs / SECONDS_PER_DAY);
+    let time = secs % SECONDS_PER_DAY;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Formats Unix seconds as a UTC date (`2026-10-24`).
+#[must_use]
+pub fn format_date(secs: u64) -> String {
+    let (year, month, day) = civil_from_days(secs / SECONDS_PER_DAY);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parses `YYYY-MM-DD` (midnight UTC) or `YYYY-MM-DDTHH:MM:SSZ` into Unix seconds.
+///
+/// Returns `None` for malformed input or dates before 1970.
+#[must_use]
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z')?)),
+        None => (value, None),
+    };
+
+    let [year, month, day] = parse_fields(date, '-')?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let seconds = match time {
+        Some(time) => {
+            let [hour, minute, second] = parse_fields(time, ':')?;
+            if hour > 23 || minute > 59 || second > 59 {
+                return None;
+            }
+            hour * 3600 + minute * 60 + second
+        },
+        None => 0,
+    };
+
+    Some(days_from_civil(year, month, day)? * SECONDS_PER_DAY + seconds)
+}
+
+fn parse_fields(value: &str, separator: char) -> Option<[u64; 3]> {
+    let mut fields = value.split(separator).map(|field| {
+        if field.is_empty() || !field.bytes().all(|c| c.is_ascii_digit()) {
+            None
+        } else {
+            field.parse().ok()
+        }
+    });
+    let parsed = [fields.next()??, fields.next()??, fields.next()??];
+    fields.next().is_none().then_some(parsed)
+}
+
+fn is_leap_year(year: u64) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    // January and February count towards the previous year; there is none before year 0
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_index = (month + 9) % 12; // March = 0
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146_097 + day_of_era).checked_sub(719_468)
+}
+
+/// Inverse of `days_from_civil`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
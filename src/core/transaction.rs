@@ -0,0 +1,141 @@
+use crate::core::dependency;
+use crate::models::package::{DbPackage, Package, PackageRepository};
+use crate::models::transaction::{
+    TransactionAction, TransactionEntry, TransactionPreview, TransactionTarget,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Compares the simulated transaction with the package list.
+///
+/// Targets that are already installed are upgrades. Targets that are not
+/// installed either replace installed packages (through `%REPLACES%` in the
+/// sync database) or are new dependencies. Installed packages that conflict
+/// with a target, in either direction, are removals too, unless they are
+/// targets themselves. Repository updates from the list that are neither
+/// ignored nor part of the transaction are reported as skipped, unless they
+/// are removed.
+///
+/// # Arguments
+///
+/// * `targets` - Packages printed by `pacman -Syu --print`
+/// * `all_packages` - All packages available for update
+/// * `ignored` - Package names passed as `--ignore`
+/// * `local` - Records of the local database
+/// * `sync` - Records of the sync databases the simulation ran against
+#[must_use]
+pub fn build_preview(
+    targets: &[TransactionTarget],
+    all_packages: &[Package],
+    ignored: &[String],
+    local: &[DbPackage],
+    sync: &[DbPackage],
+) -> TransactionPreview {
+    let installed: HashMap<&str, &str> = local
+        .iter()
+        .map(|pkg| (pkg.name.as_str(), pkg.version.as_str()))
+        .collect();
+
+    let mut removals = Vec::new();
+    let entries: Vec<TransactionEntry> = targets
+        .iter()
+        .map(|target| {
+            let action = if let Some(from) = installed.get(target.name.as_str()) {
+                TransactionAction::Upgrade {
+                    from: (*from).to_string(),
+                }
+            } else {
+                let replaces = replaced_packages(target, sync, &installed);
+                if replaces.is_empty() {
+                    TransactionAction::Install
+                } else {
+                    removals.extend(replaces.iter().cloned());
+                    TransactionAction::Replace { replaces }
+                }
+            };
+            TransactionEntry {
+                target: target.clone(),
+                action,
+            }
+        })
+        .collect();
+    removals.extend(conflicting_packages(targets, local, sync));
+
+    let in_transaction: HashSet<&str> = targets
+        .iter()
+        .map(|target| target.name.as_str())
+        .chain(removals.iter().map(String::as_str))
+        .collect();
+    let skipped = all_packages
+        .iter()
+        .filter(|pkg| pkg.repository == PackageRepository::Official)
+        .filter(|pkg| !ignored.contains(&pkg.name))
+        .filter(|pkg| !in_transaction.contains(pkg.name.as_str()))
+        .map(|pkg| pkg.name.clone())
+        .collect();
+
+    removals.sort();
+    removals.dedup();
+
+    TransactionPreview {
+        entries,
+        removals,
+        skipped,
+    }
+}
+
+/// Returns the sync record of `target`, if the databases carry it.
+fn sync_record<'a>(target: &TransactionTarget, sync: &'a [DbPackage]) -> Option<&'a DbPackage> {
+    sync.iter()
+        .find(|pkg| pkg.name == target.name && pkg.version == target.version)
+}
+
+/// Returns the installed packages `target`'s sync record replaces.
+fn replaced_packages(
+    target: &TransactionTarget,
+    sync: &[DbPackage],
+    installed: &HashMap<&str, &str>,
+) -> Vec<String> {
+    sync_record(target, sync)
+        .map(|pkg| {
+            pkg.replaces
+                .iter()
+                .map(|replaced| dependency::dependency_name(replaced))
+                .filter(|name| installed.contains_key(name))
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the installed packages that conflict with a target's sync record,
+/// or whose own `%CONFLICTS%` name a target. Installed packages that are
+/// targets themselves are upgraded rather than removed.
+fn conflicting_packages(
+    targets: &[TransactionTarget],
+    local: &[DbPackage],
+    sync: &[DbPackage],
+) -> Vec<String> {
+    let target_names: HashSet<&str> = targets.iter().map(|target| target.name.as_str()).collect();
+    let records: Vec<&DbPackage> = targets
+        .iter()
+        .filter_map(|target| sync_record(target, sync))
+        .collect();
+
+    local
+        .iter()
+        .filter(|installed| !target_names.contains(installed.name.as_str()))
+        .filter(|installed| {
+            records.iter().any(|record| {
+                record
+                    .conflicts
+                    .iter()
+                    .any(|conflict| dependency::package_satisfies(installed, conflict))
+                    || installed
+                        .conflicts
+                        .iter()
+                        .any(|conflict| dependency::package_satisfies(record, conflict))
+            })
+        })
+        .map(|installed| installed.name.clone())
+        .collect()
+}
@@ -0,0 +1,319 @@
+use crate::models::package::UpdateKind;
+use std::cmp::Ordering;
+
+/// Splits a full version string into `(epoch, pkgver, pkgrel)`.
+///
+/// Follows pacman's `parseEVR`: a missing epoch is `"0"` and the release is
+/// everything after the last `-`, if any.
+///
+/// `1:2.0-3` → `("1", "2.0", Some("3"))`, `2.0` → `("0", "2.0", None)`
+#[must_use]
+pub fn split_evr(version: &str) -> (&str, &str, Option<&str>) {
+    let digits = version.bytes().take_while(u8::is_ascii_digit).count();
+
+    let (epoch, rest) = if version.as_bytes().get(digits) == Some(&b':') {
+        let epoch = &version[..digits];
+        (
+            if epoch.is_empty() { "0" } else { epoch },
+            &version[digits + 1..],
+        )
+    } else {
+        ("0", version)
+    };
+
+    match rest.rsplit_once('-') {
+        Some((pkgver, pkgrel)) => (epoch, pkgver, Some(pkgrel)),
+        None => (epoch, rest, None),
+    }
+}
+
+/// Compares two package versions with pacman's `vercmp` semantics.
+///
+/// Epochs are compared first, then pkgver, then pkgrel — the latter only
+/// when both versions carry one, so `1.5` equals `1.5-1`.
+#[must_use]
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (epoch_a, ver_a, rel_a) = split_evr(a);
+    let (epoch_b, ver_b, rel_b) = split_evr(b);
+
+    rpmvercmp(epoch_a, epoch_b)
+        .then_with(|| rpmvercmp(ver_a, ver_b))
+        .then_with(|| match (rel_a, rel_b) {
+            (Some(rel_a), Some(rel_b)) => rpmvercmp(rel_a, rel_b),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Returns `version` without its pkgrel (`1:2.0-3` → `1:2.0`).
+#[must_use]
+pub fn strip_pkgrel(version: &str) -> &str {
+    version.rsplit_once('-').map_or(version, |(rest, _)| rest)
+}
+
+/// Returns true if `version` satisfies every comma-separated clause of `spec`.
+///
+/// Clauses are `<op><version>` with `op` one of `=`, `<`, `<=`, `>`, `>=`
+/// (default `=`), compared with `vercmp`, so `24.1.0` matches any pkgrel.
+/// A trailing `.*` matches whole segments: `6.6.*` matches `6.6.12-1` but not
+/// `6.60-1`. An empty or malformed clause matches nothing.
+#[must_use]
+pub fn matches_spec(version: &str, spec: &str) -> bool {
+    spec.split(',')
+        .all(|clause| matches_clause(version, clause.trim()))
+}
+
+fn matches_clause(version: &str, clause: &str) -> bool {
+    let (op, value) = ["<=", ">=", "<", ">", "="]
+        .iter()
+        .find_map(|op| clause.strip_prefix(op).map(|value| (*op, value.trim())))
+        .unwrap_or(("=", clause));
+    if value.is_empty() {
+        return false;
+    }
+
+    if let Some(prefix) = value.strip_suffix(".*") {
+        if op != "=" || prefix.is_empty() {
+            return false;
+        }
+        let (epoch, pkgver, _) = split_evr(version);
+        let (prefix_epoch, prefix_ver, _) = split_evr(prefix);
+        let version_segments = segments(pkgver);
+        let prefix_segments = segments(prefix_ver);
+        return rpmvercmp(epoch, prefix_epoch) == Ordering::Equal
+            && version_segments.len() >= prefix_segments.len()
+            && version_segments
+                .iter()
+                .zip(&prefix_segments)
+                .all(|(a, b)| rpmvercmp(a, b) == Ordering::Equal);
+    }
+
+    let ordering = vercmp(version, value);
+    match op {
+        "<" => ordering == Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        ">=" => ordering != Ordering::Less,
+        _ => ordering == Ordering::Equal,
+    }
+}
+
+/// Classifies the change from `current` to `new`.
+///
+/// The first differing pkgver segment decides between major, minor and patch,
+/// where segments are the alphanumeric runs `vercmp` compares
+/// (`6.9.1.arch1` → `6`, `9`, `1`, `arch`, `1`).
+#[must_use]
+pub fn classify_update(current: Option<&str>, new: &str) -> UpdateKind {
+    let Some(current) = current else {
+        return UpdateKind::Unknown;
+    };
+
+    if vercmp(new, current) == Ordering::Less {
+        return UpdateKind::Downgrade;
+    }
+
+    let (epoch_old, ver_old, _) = split_evr(current);
+    let (epoch_new, ver_new, _) = split_evr(new);
+    if rpmvercmp(epoch_old, epoch_new) != Ordering::Equal {
+        return UpdateKind::Epoch;
+    }
+
+    let old_segments = segments(ver_old);
+    let new_segments = segments(ver_new);
+    let first_change = (0..old_segments.len().max(new_segments.len())).find(|&i| {
+        match (old_segments.get(i), new_segments.get(i)) {
+            (Some(old), Some(new)) => rpmvercmp(old, new) != Ordering::Equal,
+            _ => true,
+        }
+    });
+
+    match first_change {
+        None => UpdateKind::PkgrelOnly,
+        Some(0) => UpdateKind::Major,
+        Some(1) => UpdateKind::Minor,
+        Some(_) => UpdateKind::Patch,
+    }
+}
+
+/// Splits a pkgver into its maximal digit and letter runs.
+fn segments(version: &str) -> Vec<&str> {
+    tokens(version)
+        .into_iter()
+        .filter(|token| token.bytes().all(|c| c.is_ascii_alphanumeric()))
+        .collect()
+}
+
+/// Splits a version into digit runs, letter runs and separator runs,
+/// which concatenate back to the original string.
+fn tokens(version: &str) -> Vec<&str> {
+    let bytes = version.as_bytes();
+    let class = |c: u8| {
+        if c.is_ascii_digit() {
+            0
+        } else if c.is_ascii_alphabetic() {
+            1
+        } else {
+            2
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while start < bytes.len() {
+        let kind = class(bytes[start]);
+        let len = bytes[start..]
+            .iter()
+            .take_while(|&&c| class(c) == kind)
+            .count();
+        tokens.push(&version[start..start + len]);
+        start += len;
+    }
+    tokens
+}
+
+/// Splits `new` into `(unchanged prefix, changed part, unchanged suffix)`
+/// relative to `old`, comparing whole version segments rather than characters.
+///
+/// `6.9.1.arch1-1` → `6.9.2.arch1-1` gives `("6.9.", "2", ".arch1-1")`.
+#[must_use]
+pub fn split_changed<'a>(old: &str, new: &'a str) -> (&'a str, &'a str, &'a str) {
+    let old_tokens = tokens(old);
+    let new_tokens = tokens(new);
+
+    let prefix = old_tokens
+        .iter()
+        .zip(&new_tokens)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == new_tokens.len() && prefix == old_tokens.len() {
+        return (new, "", "");
+    }
+
+    // The suffix may not overlap the prefix in either version
+    let max_suffix = (old_tokens.len() - prefix).min(new_tokens.len() - prefix);
+    let suffix = old_tokens
+        .iter()
+        .rev()
+        .zip(new_tokens.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let prefix_len: usize = new_tokens[..prefix].iter().map(|t| t.len()).sum();
+    let suffix_len: usize = new_tokens[new_tokens.len() - suffix..]
+        .iter()
+        .map(|t| t.len())
+        .sum();
+    let changed_end = new.len() - suffix_len;
+
+    (
+        &new[..prefix_len],
+        &new[prefix_len..changed_end],
+        &new[changed_end..],
+    )
+}
+
+/// Compares two version fragments segment by segment (pacman's `rpmvercmp`).
+///
+/// Segments are maximal runs of digits or letters; everything else is a
+/// separator. Numeric segments compare numerically and always beat alpha
+/// segments, and a trailing alpha segment is older than nothing at all
+/// (`1.0rc` < `1.0`).
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    let (mut one, mut two) = (0, 0);
+
+    while one < a.len() && two < b.len() {
+        let sep_start_one = one;
+        let sep_start_two = two;
+        while one < a.len() && !a[one].is_ascii_alphanumeric() {
+            one += 1;
+        }
+        while two < b.len() && !b[two].is_ascii_alphanumeric() {
+            two += 1;
+        }
+
+        if one >= a.len() || two >= b.len() {
+            break;
+        }
+
+        // Differing separator lengths decide the comparison
+        let sep_len_one = one - sep_start_one;
+        let sep_len_two = two - sep_start_two;
+        if sep_len_one != sep_len_two {
+            return sep_len_one.cmp(&sep_len_two);
+        }
+
+        let is_num = a[one].is_ascii_digit();
+        let in_segment = |c: &u8| {
+            if is_num {
+                c.is_ascii_digit()
+            } else {
+                c.is_ascii_alphabetic()
+            }
+        };
+        let end_one = one + a[one..].iter().take_while(|c| in_segment(c)).count();
+        let end_two = two + b[two..].iter().take_while(|c| in_segment(c)).count();
+
+        // Segments of different types: numeric is newer than alpha
+        if two == end_two {
+            return if is_num {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let mut seg_one = &a[one..end_one];
+        let mut seg_two = &b[two..end_two];
+
+        if is_num {
+            while seg_one.first() == Some(&b'0') {
+                seg_one = &seg_one[1..];
+            }
+            while seg_two.first() == Some(&b'0') {
+                seg_two = &seg_two[1..];
+            }
+            // Without leading zeros, the longer number is the larger one
+            let by_length = seg_one.len().cmp(&seg_two.len());
+            if by_length != Ordering::Equal {
+                return by_length;
+            }
+        }
+
+        let by_content = seg_one.cmp(seg_two);
+        if by_content != Ordering::Equal {
+            return by_content;
+        }
+
+        one = end_one;
+        two = end_two;
+    }
+
+    let rest_one = &a[one.min(a.len())..];
+    let rest_two = &b[two.min(b.len())..];
+    if rest_one.is_empty() && rest_two.is_empty() {
+        return Ordering::Equal;
+    }
+
+    // A remaining alpha segment never beats an empty string:
+    // - if one is empty and two is not alpha, two is newer
+    // - if one is alpha, two is newer
+    // - otherwise one is newer
+    let one_alpha = rest_one.first().is_some_and(u8::is_ascii_alphabetic);
+    let two_alpha = rest_two.first().is_some_and(u8::is_ascii_alphabetic);
+    if (rest_one.is_empty() && !two_alpha) || one_alpha {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
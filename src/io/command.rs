@@ -1,12 +1,24 @@
-use std::process::Command;
+use nix::sys::signal::{Signal, killpg};
+use nix::unistd::Pid;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Interval between child process status checks while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 #[derive(Debug)]
 pub enum CommandError {
     ExecutionFailed(String),
     #[allow(dead_code)]
     NotFound(String),
+    TimedOut(String),
+    Cancelled,
 }
 
 impl std::fmt::Display for CommandError {
@@ -14,22 +26,172 @@ impl std::fmt::Display for CommandError {
         match self {
             Self::ExecutionFailed(msg) => write!(f, "Command execution failed: {msg}"),
             Self::NotFound(msg) => write!(f, "Command not found: {msg}"),
+            Self::TimedOut(msg) => write!(f, "Command timed out: {msg}"),
+            Self::Cancelled => write!(f, "Command cancelled"),
         }
     }
 }
 
 impl std::error::Error for CommandError {}
 
+/// Runs a command and collects its output, killing it on timeout or cancellation.
+///
+/// The child is started in its own process group so that helpers it spawns
+/// (e.g. `pacman` under `checkupdates`) are terminated together with it.
+/// The timeout also covers helpers that keep the output pipes open after the
+/// child itself exited.
+///
+/// # Errors
+///
+/// Returns `CommandError::ExecutionFailed` if the command cannot be started,
+/// `CommandError::TimedOut` if it runs longer than `timeout`, or
+/// `CommandError::Cancelled` if `cancel` is set while it is running.
+pub fn run_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> Result<Output, CommandError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+
+    // Drain pipes on separate threads so a chatty child never blocks on a full pipe
+    let stdout_reader = spawn_pipe_reader(child.stdout.take());
+    let stderr_reader = spawn_pipe_reader(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let timed_out = || {
+        CommandError::TimedOut(format!(
+            "{program} did not finish within {}s",
+            timeout.as_secs()
+        ))
+    };
+
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?
+        {
+            // Helpers the child started (e.g. `gpg-agent`) may still hold the pipes
+            let output = wait_for_pipe(&stdout_reader, deadline, cancel).zip(wait_for_pipe(
+                &stderr_reader,
+                deadline,
+                cancel,
+            ));
+            let Some((stdout, stderr)) = output else {
+                kill_process_group(&mut child);
+                return Err(if cancel.load(Ordering::Relaxed) {
+                    CommandError::Cancelled
+                } else {
+                    timed_out()
+                });
+            };
+            return Ok(Output {
+                status,
+                stdout,
+                stderr,
+            });
+        }
+
+        if cancel.load(Ordering::Relaxed) {
+            kill_process_group(&mut child);
+            return Err(CommandError::Cancelled);
+        }
+
+        if Instant::now() >= deadline {
+            kill_process_group(&mut child);
+            return Err(timed_out());
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Reads a pipe to its end on a separate thread; the buffer is sent once the
+/// pipe closes.
+fn spawn_pipe_reader<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        let _ = tx.send(buffer);
+    });
+    rx
+}
+
+/// Waits for a pipe reader until `deadline` or until `cancel` is set.
+///
+/// Returns `None` if the pipe is still open by then.
+fn wait_for_pipe(
+    reader: &Receiver<Vec<u8>>,
+    deadline: Instant,
+    cancel: &AtomicBool,
+) -> Option<Vec<u8>> {
+    loop {
+        match reader.recv_timeout(POLL_INTERVAL) {
+            Ok(buffer) => return Some(buffer),
+            Err(RecvTimeoutError::Disconnected) => return Some(Vec::new()),
+            Err(RecvTimeoutError::Timeout) => {
+                if cancel.load(Ordering::Relaxed) || Instant::now() >= deadline {
+                    return None;
+                }
+            },
+        }
+    }
+}
+
+/// Terminates the child's whole process group and reaps the child.
+///
+/// The group outlives an exited child while its helpers run, so this also
+/// ends helpers holding the pipes. Pipe reader threads are left detached;
+/// they finish once the group exits.
+fn kill_process_group(child: &mut Child) {
+    // ESRCH means the group is already gone; the child itself is killed below either way
+    if let Ok(group) = i32::try_from(child.id()) {
+        let _ = killpg(Pid::from_raw(group), Signal::SIGTERM);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Sleeps for `duration`, waking early if `cancel` is set.
+///
+/// Returns `true` if the sleep was interrupted by cancellation.
+fn sleep_unless_cancelled(duration: Duration, cancel: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if cancel.load(Ordering::Relaxed) {
+            return true;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    cancel.load(Ordering::Relaxed)
+}
+
 /// Runs `checkupdates` to scan for official package updates with retry logic.
 ///
 /// Retries up to 3 times with 2 second delays on failure.
 /// Calls the provided callback with retry progress messages.
+/// Each attempt is killed after `timeout`; `cancel` aborts immediately.
 ///
 /// # Errors
 ///
 /// Returns `CommandError::ExecutionFailed` if the command fails to execute
 /// or returns a non-zero exit status after all retries (except exit code 2, which means no updates).
-pub fn run_checkupdates_with_callback<F>(mut on_retry: F) -> Result<String, CommandError>
+/// Returns `CommandError::TimedOut` if an attempt exceeds `timeout`, or
+/// `CommandError::Cancelled` if `cancel` is set.
+pub fn run_checkupdates_with_callback<F>(
+    timeout: Duration,
+    cancel: &AtomicBool,
+    mut on_retry: F,
+) -> Result<String, CommandError>
 where
     F: FnMut(u32, u32),
 {
@@ -39,9 +201,8 @@ where
     let mut last_error = String::new();
 
     for attempt in 1..=MAX_RETRIES {
-        let output = Command::new("checkupdates")
-            .output()
-            .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+        // A hung mirror is not retried: the timeout already covers the whole attempt
+        let output = run_with_timeout(&mut Command::new("checkupdates"), timeout, cancel)?;
 
         // Exit code 2 means no updates available (not an error)
         if output.status.success() || output.status.code() == Some(2) {
@@ -52,7 +213,9 @@ where
 
         if attempt < MAX_RETRIES {
             on_retry(attempt, MAX_RETRIES);
-            thread::sleep(Duration::from_secs(RETRY_DELAY_SECS));
+            if sleep_unless_cancelled(Duration::from_secs(RETRY_DELAY_SECS), cancel) {
+                return Err(CommandError::Cancelled);
+            }
         }
     }
 
@@ -70,21 +233,24 @@ where
 /// Returns `CommandError::ExecutionFailed` if the command fails to execute
 /// or returns a non-zero exit status after all retries (except exit code 2, which means no updates).
 #[allow(dead_code)]
-pub fn run_checkupdates() -> Result<String, CommandError> {
-    run_checkupdates_with_callback(|_, _| {})
+pub fn run_checkupdates(timeout: Duration) -> Result<String, CommandError> {
+    run_checkupdates_with_callback(timeout, &AtomicBool::new(false), |_, _| {})
 }
 
-/// Runs `paru -Qua` to query AUR package updates.
+/// Runs an AUR helper query (e.g. `paru -Qua`) to list AUR package updates.
 ///
 /// # Errors
 ///
 /// Returns `CommandError::ExecutionFailed` if the command fails to execute
-/// or returns a non-zero exit status.
-pub fn run_paru_query_aur() -> Result<String, CommandError> {
-    let output = Command::new("paru")
-        .args(["-Qua"])
-        .output()
-        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+/// or returns a non-zero exit status, `CommandError::TimedOut` if it exceeds
+/// `timeout`, or `CommandError::Cancelled` if `cancel` is set.
+pub fn run_aur_helper_query(
+    program: &str,
+    args: &[&str],
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> Result<String, CommandError> {
+    let output = run_with_timeout(Command::new(program).args(args), timeout, cancel)?;
 
     if !output.status.success() {
         return Err(CommandError::ExecutionFailed(
@@ -101,22 +267,59 @@ pub fn check_command_exists(command: &str) -> bool {
     Command::new("which")
         .arg(command)
         .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+        .is_ok_and(|output| output.status.success())
 }
 
-/// Gets the list of packages that depend on the specified package.
+/// Queries the local records of several packages with one `pacman -Qi`.
 ///
-/// Uses `pacman -Qi <package>` to retrieve the "Required By" field.
+/// Runs with `LC_ALL=C` so field names are not localized. pacman exits with an
+/// error if any package is missing but still prints the records it found, so
+/// the output is returned regardless of exit status and missing packages are
+/// left for the caller to detect.
 ///
 /// # Errors
 ///
-/// Returns `CommandError::ExecutionFailed` if the command fails or package is not found.
-pub fn get_package_required_by(package: &str) -> Result<String, CommandError> {
-    let output = Command::new("pacman")
-        .args(["-Qi", package])
-        .output()
-        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+/// Returns `CommandError::ExecutionFailed` if pacman cannot be run,
+/// or `CommandError::TimedOut` if it exceeds `timeout`.
+pub fn get_packages_required_by(
+    packages: &[String],
+    timeout: Duration,
+) -> Result<String, CommandError> {
+    let output = run_with_timeout(
+        Command::new("pacman")
+            .arg("-Qi")
+            .args(packages)
+            .env("LC_ALL", "C"),
+        timeout,
+        &AtomicBool::new(false),
+    )?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Queries package information with `pacman -Si` (sync) or `pacman -Qi` (local).
+///
+/// Runs with `LC_ALL=C` so field names and sizes are not localized. `dbpath`
+/// replaces pacman's database directory, e.g. to read the sync databases
+/// `checkupdates` refreshed instead of the system ones.
+///
+/// # Errors
+///
+/// Returns `CommandError::ExecutionFailed` if the command fails or package is not found,
+/// or `CommandError::TimedOut` if it exceeds `timeout`.
+pub fn query_package_info(
+    package: &str,
+    sync: bool,
+    dbpath: Option<&Path>,
+    timeout: Duration,
+) -> Result<String, CommandError> {
+    let flag = if sync { "-Si" } else { "-Qi" };
+    let mut command = Command::new("pacman");
+    command.args([flag, package]).env("LC_ALL", "C");
+    if let Some(dbpath) = dbpath {
+        command.arg("--dbpath").arg(dbpath);
+    }
+    let output = run_with_timeout(&mut command, timeout, &AtomicBool::new(false))?;
 
     if !output.status.success() {
         return Err(CommandError::ExecutionFailed(format!(
@@ -126,3 +329,75 @@ pub fn get_package_required_by(package: &str) -> Result<String, CommandError> {
 
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
+
+/// Runs a transaction dry run such as `pacman -Syu --print` and returns its output.
+///
+/// Runs with `LC_ALL=C` so messages are not localized.
+///
+/// # Errors
+///
+/// Returns `CommandError::ExecutionFailed` with pacman's error output if the
+/// transaction cannot be prepared, or `CommandError::TimedOut` if it exceeds `timeout`.
+pub fn simulate_transaction(cmd: &[String], timeout: Duration) -> Result<String, CommandError> {
+    let output = run_with_timeout(
+        Command::new(&cmd[0]).args(&cmd[1..]).env("LC_ALL", "C"),
+        timeout,
+        &AtomicBool::new(false),
+    )?;
+
+    if !output.status.success() {
+        return Err(CommandError::ExecutionFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Queries free space on the filesystems holding `paths` with `df`.
+///
+/// Output lists one line per path, in order, after a header line.
+///
+/// # Errors
+///
+/// Returns `CommandError::ExecutionFailed` if `df` fails (e.g. a path does not exist),
+/// or `CommandError::TimedOut` if it exceeds `timeout`.
+pub fn get_free_space(paths: &[&Path], timeout: Duration) -> Result<String, CommandError> {
+    let output = run_with_timeout(
+        Command::new("df")
+            .args(["-B1", "--output=target,avail", "--"])
+            .args(paths)
+            .env("LC_ALL", "C"),
+        timeout,
+        &AtomicBool::new(false),
+    )?;
+
+    if !output.status.success() {
+        return Err(CommandError::ExecutionFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Runs a command with inherited stdio, e.g. one that prompts for a sudo password.
+///
+/// # Errors
+///
+/// Returns an I/O error if the command fails to start.
+pub fn run_interactive(cmd: &[String]) -> std::io::Result<std::process::ExitStatus> {
+    let Some((program, args)) = cmd.split_first() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "empty command",
+        ));
+    };
+
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+}
@@ -1,5 +1,6 @@
+use crate::models::config::AurHelper;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -50,3 +51,98 @@ pub fn write_config(path: &Path, content: &str) -> Result<(), FileError> {
     }
     fs::write(path, content).map_err(|e| FileError::WriteFailed(e.to_string()))
 }
+
+/// Reads the cached `.SRCINFO` of an AUR package from the helper's clone directory.
+///
+/// Looks in `$XDG_CACHE_HOME` (default `~/.cache`):
+/// `paru/clone/<pkg>`, `yay/<pkg>`, `pikaur/aur_repos/<pkg>` or `aurutils/sync/<pkg>`.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if the helper has no clone of the package,
+/// or `FileError::ReadFailed` if reading fails for other reasons.
+pub fn read_aur_srcinfo(helper: AurHelper, package: &str) -> Result<String, FileError> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME").map_or_else(
+        || PathBuf::from(std::env::var_os("HOME").unwrap_or_default()).join(".cache"),
+        PathBuf::from,
+    );
+    let clone_dir = match helper {
+        AurHelper::Paru => cache_home.join("paru/clone"),
+        AurHelper::Yay => cache_home.join("yay"),
+        AurHelper::Pikaur => cache_home.join("pikaur/aur_repos"),
+        AurHelper::Aurutils => cache_home.join("aurutils/sync"),
+    };
+
+    fs::read_to_string(clone_dir.join(package).join(".SRCINFO")).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound
+        } else {
+            FileError::ReadFailed(e.to_string())
+        }
+    })
+}
+
+/// Lists the names of the files in a directory, e.g. the package cache.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if the directory does not exist,
+/// or `FileError::ReadFailed` if it cannot be listed.
+pub fn list_file_names(dir: &Path) -> Result<Vec<String>, FileError> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound
+        } else {
+            FileError::ReadFailed(e.to_string())
+        }
+    })?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect())
+}
+
+/// Reads `pacman.conf` from the given path. The file is never written.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if the file does not exist,
+/// or `FileError::ReadFailed` if reading fails for other reasons.
+pub fn read_pacman_conf(path: &Path) -> Result<String, FileError> {
+    read_config(path)
+}
+
+/// Reads the files named by a pacman.conf `Include` value.
+///
+/// Wildcards (`*`, `?`) are expanded in the file name only, e.g.
+/// `/etc/pacman.d/*.conf`, using `wildcard(pattern, file_name)`; matches are
+/// read in sorted order like glob(3). Unreadable files are skipped.
+#[must_use]
+pub fn read_include(pattern: &str, wildcard: impl Fn(&str, &str) -> bool) -> Vec<String> {
+    let path = Path::new(pattern);
+    let file_pattern = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !file_pattern.contains(['*', '?']) {
+        return fs::read_to_string(path).into_iter().collect();
+    }
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut matched: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| wildcard(&file_pattern, &name.to_string_lossy()))
+        })
+        .collect();
+    matched.sort();
+    matched
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect()
+}
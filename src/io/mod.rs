@@ -1,3 +1,5 @@
 pub mod command;
 pub mod file;
+pub mod pacman_db;
+pub mod source;
 pub mod terminal;
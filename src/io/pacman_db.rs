@@ -0,0 +1,262 @@
+use crate::io::file::FileError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// Upper bound for a decompressed sync database, guarding against corrupt input.
+const MAX_SYNC_DB_SIZE: usize = 512 * 1024 * 1024;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Returns the temporary database directory `checkupdates` syncs into.
+///
+/// Honours `CHECKUPDATES_DB`, otherwise `${TMPDIR:-/tmp}/checkup-db-${UID}`
+/// (older pacman-contrib releases used `${USER}` instead of the UID).
+#[must_use]
+pub fn checkupdates_dbpath() -> PathBuf {
+    if let Some(path) = std::env::var_os("CHECKUPDATES_DB") {
+        return PathBuf::from(path);
+    }
+
+    let tmp = std::env::var_os("TMPDIR").map_or_else(|| PathBuf::from("/tmp"), PathBuf::from);
+    let by_uid = fs::metadata("/proc/self")
+        .map(|meta| tmp.join(format!("checkup-db-{}", meta.uid())))
+        .ok();
+    let by_user = std::env::var("USER")
+        .ok()
+        .map(|user| tmp.join(format!("checkup-db-{user}")));
+
+    let candidates: Vec<PathBuf> = [by_uid, by_user].into_iter().flatten().collect();
+    candidates
+        .iter()
+        .find(|path| path.is_dir())
+        .or(candidates.first())
+        .cloned()
+        .unwrap_or_else(|| tmp.join("checkup-db"))
+}
+
+/// Returns the scratch database directory for a transaction dry run,
+/// `${TMPDIR:-/tmp}/par_tui-simulate-<pid>`.
+#[must_use]
+pub fn scratch_dbpath() -> PathBuf {
+    std::env::temp_dir().join(format!("par_tui-simulate-{}", std::process::id()))
+}
+
+/// Prepares `scratch` as a database directory a dry run may sync into, the
+/// way `checkupdates` prepares its own: `local` links to `<dbpath>/local` and
+/// the sync databases are copied from the first of `sync_sources` that has
+/// any. Neither the system nor the source databases are changed by a `-Sy`
+/// in `scratch`.
+///
+/// A leftover `scratch` from an interrupted run is replaced.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if no source has sync databases, or
+/// `FileError::WriteFailed` if `scratch` cannot be populated.
+pub fn prepare_scratch_dbpath(
+    scratch: &Path,
+    dbpath: &Path,
+    sync_sources: &[&Path],
+) -> Result<(), FileError> {
+    let dbs = sync_sources
+        .iter()
+        .find_map(|source| list_sync_dbs(source).ok().filter(|dbs| !dbs.is_empty()))
+        .ok_or(FileError::NotFound)?;
+
+    remove_scratch_dbpath(scratch);
+    let write_failed =
+        |e: std::io::Error| FileError::WriteFailed(format!("{}: {e}", scratch.display()));
+    let sync = scratch.join("sync");
+    fs::create_dir_all(&sync).map_err(write_failed)?;
+    std::os::unix::fs::symlink(dbpath.join("local"), scratch.join("local"))
+        .map_err(write_failed)?;
+    for (repo, path) in dbs {
+        fs::copy(&path, sync.join(format!("{repo}.db"))).map_err(write_failed)?;
+    }
+    Ok(())
+}
+
+/// Removes a scratch database directory; the `local` link is removed, not
+/// followed. Errors are ignored, as the directory lives under `TMPDIR`.
+pub fn remove_scratch_dbpath(scratch: &Path) {
+    let _ = fs::remove_dir_all(scratch);
+}
+
+/// Reads the raw `desc` records of every package in the local database.
+///
+/// Looks in `<dbpath>/local/<pkgname>-<pkgver>/desc`.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if the local database directory does not exist,
+/// or `FileError::ReadFailed` if it cannot be listed or a record cannot be read.
+pub fn read_local_db(dbpath: &Path) -> Result<Vec<String>, FileError> {
+    let local = dbpath.join("local");
+    let entries = fs::read_dir(&local).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound
+        } else {
+            FileError::ReadFailed(format!("{}: {e}", local.display()))
+        }
+    })?;
+
+    let mut records = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| FileError::ReadFailed(e.to_string()))?;
+        let desc = entry.path().join("desc");
+        // Skip non-package entries such as ALPM_DB_VERSION
+        if !desc.is_file() {
+            continue;
+        }
+        records.push(
+            fs::read_to_string(&desc)
+                .map_err(|e| FileError::ReadFailed(format!("{}: {e}", desc.display())))?,
+        );
+    }
+    Ok(records)
+}
+
+/// Lists the sync database files under `<dbpath>/sync` as `(repo, path)` pairs,
+/// sorted by repository name.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if the sync directory does not exist,
+/// or `FileError::ReadFailed` if it cannot be listed.
+pub fn list_sync_dbs(dbpath: &Path) -> Result<Vec<(String, PathBuf)>, FileError> {
+    let sync = dbpath.join("sync");
+    let entries = fs::read_dir(&sync).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound
+        } else {
+            FileError::ReadFailed(format!("{}: {e}", sync.display()))
+        }
+    })?;
+
+    let mut dbs: Vec<(String, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter_map(|path| {
+            let repo = path.file_name()?.to_str()?.strip_suffix(".db")?.to_string();
+            Some((repo, path))
+        })
+        .collect();
+    dbs.sort();
+    Ok(dbs)
+}
+
+/// Reads the raw `desc` records of every package in a sync database archive.
+///
+/// Supports gzip- or zstd-compressed and uncompressed tar archives. Records split
+/// across `desc` and `depends` files (older database format) are joined.
+///
+/// # Errors
+///
+/// Returns `FileError::NotFound` if the archive does not exist, or
+/// `FileError::ReadFailed` if it cannot be read, decompressed or unpacked.
+pub fn read_sync_db(path: &Path) -> Result<Vec<String>, FileError> {
+    let bytes = fs::read(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound
+        } else {
+            FileError::ReadFailed(format!("{}: {e}", path.display()))
+        }
+    })?;
+
+    let tar_bytes = decompress(&bytes)
+        .map_err(|e| FileError::ReadFailed(format!("{}: {e}", path.display())))?;
+
+    // Group files by their package directory, keeping a stable order
+    let mut records: BTreeMap<String, String> = BTreeMap::new();
+    let mut archive = tar::Archive::new(tar_bytes.as_slice());
+    let entries = archive
+        .entries()
+        .map_err(|e| FileError::ReadFailed(e.to_string()))?;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| FileError::ReadFailed(e.to_string()))?;
+        let entry_path = entry
+            .path()
+            .map_err(|e| FileError::ReadFailed(e.to_string()))?
+            .to_string_lossy()
+            .to_string();
+
+        let Some((dir, file)) = entry_path.rsplit_once('/') else {
+            continue;
+        };
+        if file != "desc" && file != "depends" {
+            continue;
+        }
+
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| FileError::ReadFailed(format!("{entry_path}: {e}")))?;
+
+        let record = records.entry(dir.to_string()).or_default();
+        record.push_str(&content);
+        record.push('\n');
+    }
+
+    Ok(records.into_values().collect())
+}
+
+/// Returns the tar payload of a possibly gzip- or zstd-compressed archive.
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        return zstd_decompress(bytes);
+    }
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(bytes.to_vec());
+    }
+
+    let deflate = gzip_payload(bytes).ok_or("truncated gzip header")?;
+    miniz_oxide::inflate::decompress_to_vec_with_limit(deflate, MAX_SYNC_DB_SIZE)
+        .map_err(|e| format!("gzip decompression failed: {e}"))
+}
+
+/// Decompresses a zstd frame, refusing payloads above `MAX_SYNC_DB_SIZE`.
+fn zstd_decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let decoder = ruzstd::decoding::StreamingDecoder::new(bytes)
+        .map_err(|e| format!("zstd decompression failed: {e}"))?;
+
+    let mut tar_bytes = Vec::new();
+    decoder
+        .take(MAX_SYNC_DB_SIZE as u64 + 1)
+        .read_to_end(&mut tar_bytes)
+        .map_err(|e| format!("zstd decompression failed: {e}"))?;
+    if tar_bytes.len() > MAX_SYNC_DB_SIZE {
+        return Err("zstd decompression failed: database too large".to_string());
+    }
+    Ok(tar_bytes)
+}
+
+/// Skips the gzip member header (RFC 1952) and returns the deflate stream.
+fn gzip_payload(bytes: &[u8]) -> Option<&[u8]> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    let flags = *bytes.get(3)?;
+    let mut pos = 10;
+
+    if flags & FEXTRA != 0 {
+        let len = u16::from_le_bytes([*bytes.get(pos)?, *bytes.get(pos + 1)?]);
+        pos += 2 + usize::from(len);
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            pos += bytes.get(pos..)?.iter().position(|&b| b == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+
+    bytes.get(pos..)
+}
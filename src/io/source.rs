@@ -0,0 +1,79 @@
+use crate::io::command::{self, CommandError};
+use crate::models::config::AurHelper;
+use crate::models::package::{Package, ScanSource};
+use crate::parser::{self, pacman};
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+/// A backend that can be scanned for pending package updates.
+///
+/// Implementations run their external command, translate its output into
+/// packages and report intermediate progress through the `report` callback.
+pub trait PackageSource: Send + Sync {
+    /// Identifies the source in progress lines and scan failures.
+    fn kind(&self) -> ScanSource;
+
+    /// Scans the source for pending updates.
+    ///
+    /// Running commands must be killed promptly once `cancel` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CommandError` if the underlying command fails, times out or is cancelled.
+    fn scan(
+        &self,
+        cancel: &AtomicBool,
+        report: &mut dyn FnMut(String),
+    ) -> Result<Vec<Package>, CommandError>;
+}
+
+/// Official repositories scanned via `checkupdates`.
+pub struct CheckupdatesSource {
+    pub timeout: Duration,
+}
+
+impl PackageSource for CheckupdatesSource {
+    fn kind(&self) -> ScanSource {
+        ScanSource::Official
+    }
+
+    fn scan(
+        &self,
+        cancel: &AtomicBool,
+        report: &mut dyn FnMut(String),
+    ) -> Result<Vec<Package>, CommandError> {
+        report("syncing databases...".to_string());
+        let output =
+            command::run_checkupdates_with_callback(self.timeout, cancel, |attempt, max| {
+                report(format!("retrying checkupdates (attempt {attempt}/{max})"));
+            })?;
+        Ok(pacman::parse_checkupdates_output(&output))
+    }
+}
+
+/// AUR packages scanned via the configured AUR helper.
+pub struct AurHelperSource {
+    pub helper: AurHelper,
+    pub timeout: Duration,
+}
+
+impl PackageSource for AurHelperSource {
+    fn kind(&self) -> ScanSource {
+        ScanSource::Aur
+    }
+
+    fn scan(
+        &self,
+        cancel: &AtomicBool,
+        _report: &mut dyn FnMut(String),
+    ) -> Result<Vec<Package>, CommandError> {
+        let program = self.helper.program();
+        let args: &[&str] = match self.helper {
+            AurHelper::Paru | AurHelper::Yay | AurHelper::Pikaur => &["-Qua"],
+            AurHelper::Aurutils => &["repo", "-u"],
+        };
+
+        let output = command::run_aur_helper_query(program, args, self.timeout, cancel)?;
+        Ok(parser::parse_aur_query(self.helper, &output))
+    }
+}
@@ -4,34 +4,59 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
+use std::collections::HashMap;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
 };
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
-
-use crate::io::command;
-use crate::models::config::Config;
-use crate::models::package::Package;
-use crate::parser::{pacman, paru};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::core::dependency;
+use crate::io::source::PackageSource;
+use crate::io::{command, file, pacman_db};
+use crate::models::config::{AurHelper, Config};
+use crate::models::package::{
+    DbPackage, Package, PackageDetails, PackageRelations, PackageRepository, ScanSource,
+};
+use crate::models::pacman_conf::PacmanConf;
+use crate::parser::{pacman, pacman_db as db_parser, srcinfo};
 use crate::ui::{
     app::{AppState, LoadingState, UIEvent},
     view,
 };
 
-/// Scan failure markers for warning messages
-pub const OFFICIAL_SCAN_FAILURE_MARKER: &str = "Official";
-pub const AUR_SCAN_FAILURE_MARKER: &str = "AUR";
+/// Completes the scanned packages before they are listed, e.g. with sizes
+/// from the package databases. Runs on the scan thread.
+pub type PackageAnnotator = Arc<dyn Fn(&mut [Package]) + Send + Sync>;
 
 pub enum ScanMessage {
-    Progress(String),
-    ScanWarning(String),
+    Progress(ScanSource, String),
+    ScanWarning(ScanSource, String),
     Complete(Vec<Package>),
 }
 
+/// Result of a lookup run on a worker thread so the UI keeps drawing.
+enum LookupMessage {
+    Details(String, Result<PackageDetails, String>),
+    Relations(String, Result<PackageRelations, String>),
+    Providers(HashMap<String, String>),
+}
+
+/// Settings the lookup workers need, shared by every lookup of one TUI run.
+#[derive(Clone)]
+struct LookupContext {
+    helper: AurHelper,
+    /// Sync databases `checkupdates` refreshed, if present
+    sync_dbpath: Option<PathBuf>,
+    /// pacman database holding the local (installed) records
+    local_dbpath: PathBuf,
+    timeout: Duration,
+}
+
 /// Runs the TUI with async scanning and returns the user's selected action and final state.
 ///
 /// # Errors
@@ -39,7 +64,9 @@ pub enum ScanMessage {
 /// Returns an I/O error if terminal operations fail.
 pub fn run_tui_with_scan(
     config: &Config,
-    has_paru: bool,
+    pacman_conf: &PacmanConf,
+    sources: Vec<Arc<dyn PackageSource>>,
+    annotate: PackageAnnotator,
 ) -> io::Result<(Option<UIEvent>, AppState)> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -48,14 +75,16 @@ pub fn run_tui_with_scan(
     let mut terminal = Terminal::new(backend)?;
 
     let mut state = AppState::new_loading();
+    state.load_exclusions(&config.exclude, unix_now());
+    state.pacman_conf.clone_from(pacman_conf);
 
     let (tx, rx) = mpsc::channel();
     let cancel_flag = Arc::new(AtomicBool::new(false));
-    let scan_handle = start_scan_thread(tx, has_paru, Arc::clone(&cancel_flag));
+    let scan_handle = start_scan_thread(tx, sources, annotate, Arc::clone(&cancel_flag));
 
     let result = run_app_with_loading(&mut terminal, &mut state, rx, config);
 
-    // Signal thread to stop if still running
+    // Signal workers to kill their running commands so joining is immediate
     cancel_flag.store(true, Ordering::Relaxed);
 
     // Wait for thread to complete and detect panics
@@ -70,81 +99,62 @@ pub fn run_tui_with_scan(
     result.map(|event| (event, state))
 }
 
+/// Runs the package list again on an existing state, without rescanning.
+///
+/// Used to return to the list after a dependency warning fix was applied.
+///
+/// # Errors
+///
+/// Returns an I/O error if terminal operations fail.
+pub fn run_tui_resume(state: &mut AppState, config: &Config) -> io::Result<Option<UIEvent>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // No scan is running; the sender is dropped so the channel stays empty
+    let (_, rx) = mpsc::channel();
+    let result = run_app_with_loading(&mut terminal, state, rx, config);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+/// Scans all sources concurrently, one worker thread per source.
+///
+/// Each worker reports its own progress; the coordinating thread waits for
+/// every worker, runs `annotate` over all packages and then sends a single
+/// `Complete`, so a failing source never blocks the others.
 fn start_scan_thread(
     tx: Sender<ScanMessage>,
-    has_paru: bool,
+    sources: Vec<Arc<dyn PackageSource>>,
+    annotate: PackageAnnotator,
     cancel_flag: Arc<AtomicBool>,
 ) -> JoinHandle<()> {
     thread::spawn(move || {
-        // Helper macro to send message and return early if channel is closed
-        macro_rules! send_or_return {
-            ($msg:expr) => {
-                if tx.send($msg).is_err() {
-                    return;
-                }
-            };
-        }
+        let workers: Vec<(ScanSource, JoinHandle<Option<Vec<Package>>>)> = sources
+            .into_iter()
+            .map(|source| {
+                let kind = source.kind();
+                let tx = tx.clone();
+                let cancel_flag = Arc::clone(&cancel_flag);
+                let handle = thread::spawn(move || scan_source(source.as_ref(), &tx, &cancel_flag));
+                (kind, handle)
+            })
+            .collect();
 
         let mut all_packages = Vec::new();
-        let mut official_failed = false;
-        let mut aur_failed = false;
-
-        // Scan official packages
-        if cancel_flag.load(Ordering::Relaxed) {
-            return;
-        }
-
-        send_or_return!(ScanMessage::Progress(
-            "Scanning official repositories...".to_string()
-        ));
-
-        let tx_clone = tx.clone();
-        match command::run_checkupdates_with_callback(|attempt, max| {
-            let _ = tx_clone.send(ScanMessage::Progress(format!(
-                "Retrying checkupdates (attempt {attempt}/{max})"
-            )));
-        }) {
-            Ok(output) => {
-                let packages = pacman::parse_checkupdates_output(&output);
-                let count = packages.len();
-                send_or_return!(ScanMessage::Progress(format!(
-                    "Found {} official update{}",
-                    count,
-                    if count == 1 { "" } else { "s" }
-                )));
-                all_packages.extend(packages);
-            },
-            Err(e) => {
-                official_failed = true;
-                send_or_return!(ScanMessage::Progress(format!(
-                    "Warning: Could not scan official repos: {e:?}"
-                )));
-            },
-        }
+        let mut failed_sources = Vec::new();
 
-        // Scan AUR packages
-        if has_paru && !cancel_flag.load(Ordering::Relaxed) {
-            send_or_return!(ScanMessage::Progress(
-                "Scanning AUR packages...".to_string()
-            ));
-
-            match command::run_paru_query_aur() {
-                Ok(output) => {
-                    let packages = paru::parse_paru_output(&output);
-                    let count = packages.len();
-                    send_or_return!(ScanMessage::Progress(format!(
-                        "Found {} AUR update{}",
-                        count,
-                        if count == 1 { "" } else { "s" }
-                    )));
-                    all_packages.extend(packages);
-                },
-                Err(e) => {
-                    aur_failed = true;
-                    send_or_return!(ScanMessage::Progress(format!(
-                        "Warning: Could not scan AUR packages: {e:?}"
-                    )));
-                },
+        // Join in source order so the package list order stays deterministic
+        for (kind, handle) in workers {
+            match handle.join() {
+                Ok(Some(packages)) => all_packages.extend(packages),
+                Ok(None) | Err(_) => failed_sources.push(kind),
             }
         }
 
@@ -153,33 +163,50 @@ fn start_scan_thread(
             return;
         }
 
-        // Final status message
-        let total = all_packages.len();
-        send_or_return!(ScanMessage::Progress(format!(
-            "Scan complete. Total: {} update{}",
-            total,
-            if total == 1 { "" } else { "s" }
-        )));
-
-        // Send warning about scan failures
-        if official_failed || aur_failed {
-            let mut failed_sources = Vec::new();
-            if official_failed {
-                failed_sources.push(OFFICIAL_SCAN_FAILURE_MARKER);
-            }
-            if aur_failed {
-                failed_sources.push(AUR_SCAN_FAILURE_MARKER);
+        // Report each failed source so the UI can decide on retry options
+        for kind in failed_sources {
+            if tx
+                .send(ScanMessage::ScanWarning(
+                    kind,
+                    format!("{kind} scan failed"),
+                ))
+                .is_err()
+            {
+                return;
             }
-            send_or_return!(ScanMessage::ScanWarning(format!(
-                "{} scan failed",
-                failed_sources.join(" & ")
-            )));
         }
 
-        send_or_return!(ScanMessage::Complete(all_packages));
+        annotate(&mut all_packages);
+        let _ = tx.send(ScanMessage::Complete(all_packages));
     })
 }
 
+/// Scans a single source, reporting progress under its own label.
+///
+/// Returns `None` if the scan failed or was cancelled.
+fn scan_source(
+    source: &dyn PackageSource,
+    tx: &Sender<ScanMessage>,
+    cancel_flag: &AtomicBool,
+) -> Option<Vec<Package>> {
+    let kind = source.kind();
+    let _ = tx.send(ScanMessage::Progress(kind, "scanning...".to_string()));
+
+    match source.scan(cancel_flag, &mut |message| {
+        let _ = tx.send(ScanMessage::Progress(kind, message));
+    }) {
+        Ok(packages) => {
+            let count = packages.len();
+            let _ = tx.send(ScanMessage::Progress(kind, format!("{count} found")));
+            Some(packages)
+        },
+        Err(e) => {
+            let _ = tx.send(ScanMessage::Progress(kind, format!("failed: {e}")));
+            None
+        },
+    }
+}
+
 // Clippy suggests taking `&Receiver` here, but the event loop needs to own
 // the `Receiver<ScanMessage>` and consume it (calling `try_recv` in a loop),
 // so we intentionally pass it by value and suppress `needless_pass_by_value`.
@@ -190,17 +217,39 @@ fn run_app_with_loading(
     rx: Receiver<ScanMessage>,
     config: &Config,
 ) -> io::Result<Option<UIEvent>> {
+    let query_timeout = Duration::from_secs(config.timeouts.pacman_query);
+    let lookups = LookupContext {
+        helper: config.helper.name.unwrap_or_default(),
+        sync_dbpath: Some(pacman_db::checkupdates_dbpath())
+            .filter(|path| path.join("sync").is_dir()),
+        local_dbpath: config.pacman.dbpath.clone(),
+        timeout: query_timeout,
+    };
+    let (lookup_tx, lookup_rx) = mpsc::channel();
+    let mut providers_requested = false;
+
     loop {
+        start_lookups(state, &lookup_tx, &lookups, &mut providers_requested);
+        while let Ok(msg) = lookup_rx.try_recv() {
+            match msg {
+                LookupMessage::Details(name, details) => state.finish_details(name, details),
+                LookupMessage::Relations(name, relations) => {
+                    state.finish_relations(name, relations);
+                },
+                LookupMessage::Providers(index) => state.provider_index = Some(index),
+            }
+        }
+
         terminal.draw(|f| view::render(f, state))?;
 
-        // Check for scan messages
-        if let Ok(msg) = rx.try_recv() {
+        // Drain scan messages (sources report concurrently)
+        while let Ok(msg) = rx.try_recv() {
             match msg {
-                ScanMessage::Progress(message) => {
-                    state.set_loading_message(message);
+                ScanMessage::Progress(source, message) => {
+                    state.set_source_progress(source, message);
                 },
-                ScanMessage::ScanWarning(warning) => {
-                    state.add_scan_warning(warning);
+                ScanMessage::ScanWarning(source, warning) => {
+                    state.add_scan_warning(source, warning);
                 },
                 ScanMessage::Complete(packages) => {
                     if packages.is_empty() {
@@ -226,6 +275,20 @@ fn run_app_with_loading(
                 continue;
             }
 
+            // The note prompt captures typing, including `q`
+            if state.note_input.is_some() {
+                handle_note_input(state, key.code);
+                continue;
+            }
+
+            // The graph browser replaces the list until closed
+            if state.graph.is_some() && key.code != KeyCode::Char('q') {
+                handle_graph_input(state, key.code);
+                continue;
+            }
+
+            state.status_message = None;
+
             match (&state.loading_state, key.code) {
                 // Allow quit in any state
                 (_, KeyCode::Char('q')) => return Ok(Some(UIEvent::Quit)),
@@ -239,13 +302,21 @@ fn run_app_with_loading(
 
                 // Only allow other keys when ready
                 (LoadingState::Ready, KeyCode::Char('?')) => state.toggle_help(),
+                (LoadingState::Ready, KeyCode::Char('i')) => state.toggle_details(),
                 (LoadingState::Ready, KeyCode::Char('j') | KeyCode::Down) => {
                     state.move_cursor_down();
                 },
                 (LoadingState::Ready, KeyCode::Char('k') | KeyCode::Up) => {
                     state.move_cursor_up();
                 },
-                (LoadingState::Ready, KeyCode::Char('p')) => state.toggle_permanent_ignore(),
+                (LoadingState::Ready, KeyCode::Char('d')) => state.open_graph(),
+                (LoadingState::Ready, KeyCode::Char('p')) => state.begin_permanent_ignore(),
+                (LoadingState::Ready, KeyCode::Char('h')) => state.toggle_hold(),
+                (LoadingState::Ready, KeyCode::Char('g')) => state.toggle_group_ignore(),
+                (LoadingState::Ready, KeyCode::Char('R')) => state.toggle_repo_ignore(),
+                (LoadingState::Ready, KeyCode::Char('s')) => {
+                    state.toggle_snooze(unix_now(), config.behavior.snooze_days);
+                },
                 (LoadingState::Ready, KeyCode::Char(' ')) => state.toggle_current_package(),
                 (LoadingState::Ready, KeyCode::Char('o')) => {
                     state.pending_action = Some(UIEvent::UpdateOfficialOnly);
@@ -261,8 +332,157 @@ fn run_app_with_loading(
     }
 }
 
-/// Runs the TUI for dependency conflict confirmation modal only.
-/// State must already have `dependency_conflicts` set and `show_dependency_warning` = true.
+/// Edits the exclusion note prompt: Enter excludes the package, Esc cancels.
+fn handle_note_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Enter => state.confirm_permanent_note(unix_now()),
+        KeyCode::Esc => state.cancel_permanent_note(),
+        KeyCode::Backspace => {
+            if let Some(input) = state.note_input.as_mut() {
+                input.pop();
+            }
+        },
+        KeyCode::Char(c) => {
+            if let Some(input) = state.note_input.as_mut() {
+                input.push(c);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Navigates the dependency graph browser.
+fn handle_graph_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => state.graph_cursor_down(),
+        KeyCode::Char('k') | KeyCode::Up => state.graph_cursor_up(),
+        KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => state.graph_enter(),
+        KeyCode::Backspace | KeyCode::Char('h') | KeyCode::Left => state.graph_back(),
+        KeyCode::Esc | KeyCode::Char('d') => state.close_graph(),
+        _ => {},
+    }
+}
+
+/// Returns the current time as Unix seconds (0 if the clock is before 1970).
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Starts worker threads for lookups the current view needs.
+///
+/// Workers are detached; each sends one message and exits once its `pacman`
+/// query finishes or times out. The provider index for the graph browser is
+/// read once per run, when the browser first opens.
+fn start_lookups(
+    state: &mut AppState,
+    tx: &Sender<LookupMessage>,
+    lookups: &LookupContext,
+    providers_requested: &mut bool,
+) {
+    if state.show_details
+        && state.loading_state == LoadingState::Ready
+        && let Some(pkg) = state.request_current_details()
+    {
+        let tx = tx.clone();
+        let lookups = lookups.clone();
+        thread::spawn(move || {
+            let details = fetch_package_details(&pkg, &lookups);
+            let _ = tx.send(LookupMessage::Details(pkg.name, details));
+        });
+    }
+
+    if let Some(name) = state.request_graph_relations() {
+        let tx = tx.clone();
+        let lookups = lookups.clone();
+        thread::spawn(move || {
+            let relations = fetch_package_relations(&name, &lookups);
+            let _ = tx.send(LookupMessage::Relations(name, relations));
+        });
+    }
+
+    if state.graph.is_some() && state.provider_index.is_none() && !*providers_requested {
+        *providers_requested = true;
+        let tx = tx.clone();
+        let dbpath = lookups.local_dbpath.clone();
+        thread::spawn(move || {
+            let _ = tx.send(LookupMessage::Providers(read_provider_index(&dbpath)));
+        });
+    }
+}
+
+/// Looks up info pane details for a package.
+///
+/// Official packages are read from `pacman -Si` on the sync databases the list
+/// came from; AUR packages from the helper's cached `.SRCINFO`, falling back
+/// to the installed version's `pacman -Qi`.
+fn fetch_package_details(pkg: &Package, lookups: &LookupContext) -> Result<PackageDetails, String> {
+    let timeout = lookups.timeout;
+    let installed = command::query_package_info(&pkg.name, false, None, timeout)
+        .ok()
+        .map(|output| pacman::parse_package_info(&output));
+
+    let mut details = match pkg.repository {
+        PackageRepository::Official => {
+            command::query_package_info(&pkg.name, true, lookups.sync_dbpath.as_deref(), timeout)
+                .map(|output| pacman::parse_package_info(&output))
+                .map_err(|e| e.to_string())?
+        },
+        PackageRepository::Aur => file::read_aur_srcinfo(lookups.helper, &pkg.name)
+            .ok()
+            .and_then(|content| srcinfo::parse_srcinfo(&content, &pkg.name))
+            .or_else(|| {
+                // Only the installed version is known; its sizes say nothing about the update
+                installed.clone().map(|details| PackageDetails {
+                    installed_size: None,
+                    download_size: None,
+                    ..details
+                })
+            })
+            .ok_or_else(|| format!("No information available for '{}'", pkg.name))?,
+    };
+
+    details.current_installed_size = installed.and_then(|details| details.installed_size);
+    Ok(details)
+}
+
+/// Looks up a package's graph neighbours, installed version first.
+///
+/// Packages that are not installed are read from `pacman -Si` on the sync
+/// databases the list came from, which has no "Required By" field.
+fn fetch_package_relations(
+    name: &str,
+    lookups: &LookupContext,
+) -> Result<PackageRelations, String> {
+    let timeout = lookups.timeout;
+    command::query_package_info(name, false, None, timeout)
+        .or_else(|_| {
+            command::query_package_info(name, true, lookups.sync_dbpath.as_deref(), timeout)
+        })
+        .map(|output| pacman::parse_package_relations(&output))
+        .map_err(|e| e.to_string())
+}
+
+/// Reads what installed packages provide from the local database.
+///
+/// An unreadable database gives an empty index, so entries are then
+/// navigated to by name.
+fn read_provider_index(dbpath: &Path) -> HashMap<String, String> {
+    let local: Vec<DbPackage> = pacman_db::read_local_db(dbpath)
+        .map(|records| {
+            records
+                .iter()
+                .filter_map(|record| db_parser::parse_desc(record))
+                .collect()
+        })
+        .unwrap_or_default();
+    dependency::build_provider_index(&local)
+}
+
+/// Runs the TUI for a confirmation modal only.
+/// State must already have either `dependency_conflicts` set and `show_dependency_warning` = true,
+/// `disk_shortages` set, or `transaction_preview` set.
 ///
 /// # Errors
 ///
@@ -290,15 +510,30 @@ enum ModalResult {
     IgnoreKey,
 }
 
-fn handle_dependency_warning_modal(
-    state: &mut AppState,
-    key_code: KeyCode,
-) -> ModalResult {
+fn handle_dependency_warning_modal(state: &mut AppState, key_code: KeyCode) -> ModalResult {
     match key_code {
         KeyCode::Char('y') => {
             state.toggle_dependency_warning();
             ModalResult::Proceed(state.pending_action.take())
         },
+        KeyCode::Char('i') => {
+            state.ignore_conflict_dependents();
+            state.toggle_dependency_warning();
+            state.pending_action = None;
+            ModalResult::Proceed(Some(UIEvent::RecheckDependencies))
+        },
+        KeyCode::Char('H') if !state.hold_trees.is_empty() => {
+            state.hold_all_dependents();
+            state.toggle_dependency_warning();
+            state.pending_action = None;
+            ModalResult::Proceed(Some(UIEvent::RecheckDependencies))
+        },
+        KeyCode::Char('u') => {
+            state.unignore_conflicting();
+            state.toggle_dependency_warning();
+            state.pending_action = None;
+            ModalResult::Proceed(Some(UIEvent::RecheckDependencies))
+        },
         KeyCode::Char('n') | KeyCode::Esc => {
             state.toggle_dependency_warning();
             state.pending_action = None;
@@ -312,6 +547,59 @@ fn handle_dependency_warning_modal(
     }
 }
 
+fn handle_disk_warning_modal(state: &mut AppState, key_code: KeyCode) -> ModalResult {
+    match key_code {
+        KeyCode::Char('c') => {
+            // Keep the pending action so the update can continue after cleanup
+            state.disk_shortages.clear();
+            ModalResult::Proceed(Some(UIEvent::CleanCache))
+        },
+        KeyCode::Char('y') => {
+            state.disk_shortages.clear();
+            ModalResult::Proceed(state.pending_action.take())
+        },
+        KeyCode::Char('n') | KeyCode::Esc => {
+            state.disk_shortages.clear();
+            state.pending_action = None;
+            ModalResult::Cancel
+        },
+        KeyCode::Char('q') => {
+            state.disk_shortages.clear();
+            state.pending_action = None;
+            ModalResult::Quit
+        },
+        _ => ModalResult::IgnoreKey,
+    }
+}
+
+fn handle_transaction_preview_modal(state: &mut AppState, key_code: KeyCode) -> ModalResult {
+    match key_code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            state.transaction_scroll_down();
+            ModalResult::IgnoreKey
+        },
+        KeyCode::Char('k') | KeyCode::Up => {
+            state.transaction_scroll_up();
+            ModalResult::IgnoreKey
+        },
+        KeyCode::Char('y') | KeyCode::Enter => {
+            state.transaction_preview = None;
+            ModalResult::Proceed(state.pending_action.take())
+        },
+        KeyCode::Char('n') | KeyCode::Esc => {
+            state.transaction_preview = None;
+            state.pending_action = None;
+            ModalResult::Cancel
+        },
+        KeyCode::Char('q') => {
+            state.transaction_preview = None;
+            state.pending_action = None;
+            ModalResult::Quit
+        },
+        _ => ModalResult::IgnoreKey,
+    }
+}
+
 fn run_modal_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
@@ -321,7 +609,13 @@ fn run_modal_loop(
 
         if event::poll(Duration::from_millis(100))?
             && let Event::Key(key) = event::read()?
-            && let result = handle_dependency_warning_modal(state, key.code)
+            && let result = if state.has_disk_shortages() {
+                handle_disk_warning_modal(state, key.code)
+            } else if state.has_transaction_preview() {
+                handle_transaction_preview_modal(state, key.code)
+            } else {
+                handle_dependency_warning_modal(state, key.code)
+            }
             && !matches!(result, ModalResult::IgnoreKey)
         {
             return Ok(match result {
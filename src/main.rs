@@ -5,35 +5,77 @@ mod parser;
 mod ui;
 
 use core::planner::{self, UpdateMode};
+use io::source::{AurHelperSource, CheckupdatesSource, PackageSource};
 use io::{command, file, terminal};
-use parser::{pacman, toml as toml_parser};
-use std::path::PathBuf;
+use parser::{pacman, pacman_conf, toml as toml_parser};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use ui::app::UIEvent;
 
+/// Outcome of the confirmation steps before an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirmation {
+    Proceed,
+    Cancel,
+    /// A dependency warning fix was applied; show the package list again
+    BackToList,
+}
+
 fn handle_update(
     final_state: &mut ui::app::AppState,
     all_packages: Vec<models::package::Package>,
     config: &models::config::Config,
     mode: UpdateMode,
-) -> bool {
+) -> Confirmation {
+    let mode = if mode == UpdateMode::EntireSystem && config.helper.name.is_none() {
+        eprintln!("Warning: no AUR helper found. Updating official packages with pacman only.");
+        UpdateMode::OfficialOnly
+    } else {
+        mode
+    };
     let ignored = final_state.get_ignored_packages();
+    let ignored_groups = final_state.get_ignored_groups();
+
+    let confirmed = check_and_confirm_dependencies(final_state, &all_packages, &ignored, config)
+        .and_then(|confirmation| {
+            if confirmation != Confirmation::Proceed {
+                return Ok(confirmation);
+            }
+            let proceed = check_and_confirm_disk_space(final_state, config, mode)?
+                && (!config.behavior.simulate_transaction
+                    || check_and_confirm_transaction(
+                        final_state,
+                        &all_packages,
+                        &ignored,
+                        &ignored_groups,
+                        config,
+                        mode,
+                    )?);
+            Ok(if proceed {
+                Confirmation::Proceed
+            } else {
+                Confirmation::Cancel
+            })
+        });
 
-    match check_and_confirm_dependencies(final_state, &all_packages, &ignored) {
-        Ok(true) => {
-            execute_update(mode, all_packages, ignored, config);
-            true
+    match confirmed {
+        Ok(Confirmation::Proceed) => {
+            execute_update(mode, all_packages, ignored, ignored_groups, config);
+            Confirmation::Proceed
         },
-        Ok(false) => {
-            // User cancelled, do nothing
-            false
+        Ok(confirmation) => {
+            // User cancelled or went back to the list, do nothing
+            confirmation
         },
         Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
             // User quit during confirmation
-            false
+            Confirmation::Cancel
         },
         Err(e) => {
             eprintln!("Failed to check dependencies: {e}");
-            false
+            Confirmation::Cancel
         },
     }
 }
@@ -48,81 +90,164 @@ fn main() {
     });
     let config_path = PathBuf::from(config_home).join("partui/config.toml");
 
-    let config = if let Ok(content) = file::read_config(&config_path) {
+    let mut config = if let Ok(content) = file::read_config(&config_path) {
         toml_parser::parse_config(&content).unwrap_or_default()
     } else {
         models::config::Config::default()
     };
 
+    let pacman_conf = load_pacman_conf(&config.pacman.conf);
+
     // Check for required commands
     let has_checkupdates = command::check_command_exists("checkupdates");
-    let has_paru = command::check_command_exists("paru");
 
     if !has_checkupdates {
         eprintln!("Error: checkupdates not found. Install pacman-contrib.");
         return;
     }
 
-    // Launch TUI with async scanning (loop for reload)
-    loop {
-        match terminal::run_tui_with_scan(&config, has_paru) {
-            Ok((Some(UIEvent::Reload), _)) => {
-                // Reload: restart scan, do not save config
-            },
-            Ok((Some(event), mut final_state)) => {
-                // Terminating event: save config if changed, then execute
-                // Skip saving if state is not ready (e.g., quit during scan)
-                if final_state.is_ready() {
-                    save_config_if_changed(&config_path, &config, &final_state);
-                }
+    // Resolve the AUR helper: configured, else auto-detected
+    let detected_helper = match config.helper.name {
+        Some(helper) if command::check_command_exists(helper.program()) => Some(helper),
+        Some(helper) => {
+            eprintln!(
+                "Warning: configured AUR helper '{}' not found. AUR scan disabled.",
+                helper.program()
+            );
+            None
+        },
+        None => detect_aur_helper(),
+    };
 
-                // Get all packages from final state
-                let all_packages: Vec<models::package::Package> = final_state
-                    .packages
-                    .iter()
-                    .map(|item| item.package.clone())
-                    .collect();
-
-                match event {
-                    UIEvent::UpdateEntireSystem => {
-                        handle_update(&mut final_state, all_packages, &config, UpdateMode::EntireSystem);
-                    },
-                    UIEvent::UpdateOfficialOnly => {
-                        handle_update(&mut final_state, all_packages, &config, UpdateMode::OfficialOnly);
-                    },
-                    UIEvent::Quit => {},
-                    UIEvent::Reload => {
-                        panic!(
-                            "DESIGN VIOLATION: UIEvent::Reload must be handled by the outer loop (Ok((Some(UIEvent::Reload), _)))"
-                        )
-                    },
-                }
-                break;
+    // No helper means no AUR updates; EntireSystem then falls back to pacman
+    let mut run_config = config.clone();
+    run_config.helper.name = detected_helper;
+    run_config.pacman.cachedir = config
+        .pacman
+        .cachedir
+        .clone()
+        .or_else(|| pacman_conf.cache_dirs.first().map(PathBuf::from));
+
+    let mut sources: Vec<Arc<dyn PackageSource>> = vec![Arc::new(CheckupdatesSource {
+        timeout: Duration::from_secs(config.timeouts.checkupdates),
+    })];
+    if let Some(helper) = detected_helper {
+        sources.push(Arc::new(AurHelperSource {
+            helper,
+            timeout: Duration::from_secs(config.timeouts.aur_helper),
+        }));
+    }
+
+    // Sizes and origins come from the databases the official scan refreshed
+    let dbpath = config.pacman.dbpath.clone();
+    let annotate: terminal::PackageAnnotator =
+        Arc::new(move |packages| annotate_from_databases(packages, &dbpath));
+
+    // Launch TUI with async scanning (loop for reload); a dependency warning
+    // fix returns to the same list instead of scanning again
+    let mut resume: Option<ui::app::AppState> = None;
+    loop {
+        let resumed = resume.is_some();
+        let outcome = match resume.take() {
+            Some(mut state) => {
+                terminal::run_tui_resume(&mut state, &run_config).map(|event| (event, state))
             },
+            None => terminal::run_tui_with_scan(
+                &run_config,
+                &pacman_conf,
+                sources.clone(),
+                Arc::clone(&annotate),
+            ),
+        };
+        let (event, mut final_state) = match outcome {
+            Ok((Some(event), state)) => (event, state),
             Ok((None, _)) => break,
             Err(e) => {
                 eprintln!("TUI error: {e}");
                 break;
             },
+        };
+
+        // Reload: restart scan, do not save config
+        if !resumed && matches!(event, UIEvent::Reload) {
+            continue;
+        }
+
+        // Terminating event: save config if changed, then execute
+        // Skip saving if state is not ready (e.g., quit during scan)
+        if final_state.is_ready() {
+            save_config_if_changed(&config_path, &mut config, &final_state);
+            // A reload from a resumed list must seed the rescan with what was just saved
+            run_config.exclude.clone_from(&config.exclude);
+        }
+
+        let mode = match event {
+            UIEvent::UpdateEntireSystem => UpdateMode::EntireSystem,
+            UIEvent::UpdateOfficialOnly => UpdateMode::OfficialOnly,
+            UIEvent::Reload => continue,
+            UIEvent::Quit | UIEvent::CleanCache | UIEvent::RecheckDependencies => break,
+        };
+
+        // Get all packages from final state
+        let all_packages: Vec<models::package::Package> = final_state
+            .packages
+            .iter()
+            .map(|item| item.package.clone())
+            .collect();
+
+        if handle_update(&mut final_state, all_packages, &run_config, mode)
+            != Confirmation::BackToList
+        {
+            break;
         }
+        resume = Some(final_state);
     }
 }
 
+/// Reads `pacman.conf` and its `Include`d files; it is never written.
+///
+/// An unreadable file gives an empty configuration.
+fn load_pacman_conf(path: &Path) -> models::pacman_conf::PacmanConf {
+    file::read_pacman_conf(path)
+        .map(|content| {
+            pacman_conf::parse_pacman_conf(&content, |pattern| {
+                file::read_include(pattern, core::filter::glob_match)
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the first installed AUR helper, preferring paru.
+fn detect_aur_helper() -> Option<models::config::AurHelper> {
+    use models::config::AurHelper;
+
+    [
+        AurHelper::Paru,
+        AurHelper::Yay,
+        AurHelper::Pikaur,
+        AurHelper::Aurutils,
+    ]
+    .into_iter()
+    .find(|&helper| command::check_command_exists(helper.program()))
+}
+
+/// Saves exclusions changed in the TUI and updates `config` to match, so a
+/// later call only saves newer changes.
 fn save_config_if_changed(
     config_path: &std::path::Path,
-    config: &models::config::Config,
+    config: &mut models::config::Config,
     final_state: &ui::app::AppState,
 ) {
-    let new_permanent = final_state.get_permanent_excludes();
-    if new_permanent != config.exclude.permanent {
+    if let Some(exclude) = final_state.changed_exclusions(&config.exclude) {
         let mut updated_config = config.clone();
-        updated_config.exclude.permanent = new_permanent;
+        updated_config.exclude = exclude;
         match toml_parser::serialize_config(&updated_config) {
             Ok(content) => {
                 if let Err(e) = file::write_config(config_path, &content) {
                     eprintln!("Warning: Could not save config: {e:?}");
                 } else {
-                    println!("Permanent excludes saved to config.");
+                    println!("Exclusions saved to config.");
+                    *config = updated_config;
                 }
             },
             Err(e) => {
@@ -136,51 +261,298 @@ fn check_and_confirm_dependencies(
     state: &mut ui::app::AppState,
     all_packages: &[models::package::Package],
     ignored: &[String],
-) -> std::io::Result<bool> {
-    // Perform dependency check (orchestration: main.rs calls core and parser)
-    match core::dependency::check_conflicts(all_packages, ignored, |pkg| {
-        state.get_or_fetch_required_by(pkg, || {
-            command::get_package_required_by(pkg)
-                .map(|output| pacman::parse_required_by(&output))
-                .map_err(|e| e.to_string())
-        })
-    }) {
-        Ok(conflicts) => {
-            if conflicts.is_empty() {
+    config: &models::config::Config,
+) -> std::io::Result<Confirmation> {
+    match find_dependency_problems(state, all_packages, ignored, config) {
+        Ok(problems) => {
+            if problems.is_empty() {
                 // No conflicts, proceed
-                return Ok(true);
+                return Ok(Confirmation::Proceed);
             }
 
             // Conflicts found, show modal for user decision
-            state.set_dependency_conflicts(conflicts);
-            state.show_dependency_warning = true;
+            state.set_dependency_problems(problems);
 
             // Re-enter TUI for confirmation
             match terminal::run_tui_for_confirmation(state)? {
-                Some(UIEvent::UpdateEntireSystem | UIEvent::UpdateOfficialOnly) => Ok(true),
+                Some(UIEvent::UpdateEntireSystem | UIEvent::UpdateOfficialOnly) => {
+                    Ok(Confirmation::Proceed)
+                },
+                Some(UIEvent::RecheckDependencies) => {
+                    // A fix was applied; recheck and show the list again
+                    let ignored = state.get_ignored_packages();
+                    let result = find_dependency_problems(state, all_packages, &ignored, config);
+                    state.finish_dependency_recheck(&core::dependency::recheck_summary(&result));
+                    Ok(Confirmation::BackToList)
+                },
                 Some(UIEvent::Quit) => Err(std::io::Error::new(
                     std::io::ErrorKind::Interrupted,
                     "User quit during dependency confirmation",
                 )),
-                _ => Ok(false), // User cancelled
+                _ => Ok(Confirmation::Cancel), // User cancelled
             }
         },
         Err(warnings) => {
             for warning in warnings {
                 eprintln!("Dependency check warning: {warning}");
             }
-            Ok(false) // Don't proceed if dependency check failed
+            Ok(Confirmation::Cancel) // Don't proceed if dependency check failed
         },
     }
 }
 
+/// Runs the dependency checks for the given ignore list against the local
+/// database and the sync databases `checkupdates` refreshed.
+///
+/// If the local database is unreadable, reverse dependencies are read with
+/// `pacman -Qi` instead, cached in `state`.
+fn find_dependency_problems(
+    state: &mut ui::app::AppState,
+    all_packages: &[models::package::Package],
+    ignored: &[String],
+    config: &models::config::Config,
+) -> Result<core::dependency::DependencyProblems, Vec<String>> {
+    let local = load_local_packages(&config.pacman.dbpath);
+    let sync = if local.is_some() {
+        load_sync_packages(&io::pacman_db::checkupdates_dbpath())
+    } else {
+        Vec::new()
+    };
+
+    // Query all ignored packages with one `pacman -Qi` before single lookups
+    let timeout = Duration::from_secs(config.timeouts.pacman_query);
+    let mut missing: Option<HashMap<String, String>> = None;
+    let query = |pkg: &str| {
+        let missing = missing.get_or_insert_with(|| {
+            state.prefetch_required_by(ignored, |names| query_required_by(names, timeout))
+        });
+        if let Some(error) = missing.get(pkg) {
+            return (Vec::new(), Some(error.clone()));
+        }
+        state.get_or_fetch_required_by(pkg, || {
+            query_required_by(&[pkg.to_string()], timeout)?
+                .remove(pkg)
+                .ok_or_else(|| format!("Package '{pkg}' not found"))
+        })
+    };
+
+    core::dependency::find_problems(
+        all_packages,
+        ignored,
+        local.as_deref(),
+        &sync,
+        config.behavior.transitive_holds,
+        query,
+    )
+}
+
+/// Reads the reverse dependencies of `packages` with one `pacman -Qi` call.
+fn query_required_by(
+    packages: &[String],
+    timeout: Duration,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    command::get_packages_required_by(packages, timeout)
+        .map(|output| pacman::parse_required_by_records(&output))
+        .map_err(|e| e.to_string())
+}
+
+/// Checks free space on the package cache and root filesystem before updating.
+///
+/// Shows a blocking modal when space is short; cleaning the cache from the
+/// modal checks again. If free space cannot be determined, the update proceeds
+/// with a warning.
+fn check_and_confirm_disk_space(
+    state: &mut ui::app::AppState,
+    config: &models::config::Config,
+    mode: UpdateMode,
+) -> std::io::Result<bool> {
+    use core::preflight::SpaceVerdict;
+
+    let cachedir = config
+        .pacman
+        .cachedir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(models::config::DEFAULT_CACHEDIR));
+    let timeout = Duration::from_secs(config.timeouts.pacman_query);
+
+    loop {
+        let cache_files = file::list_file_names(&cachedir).unwrap_or_default();
+        let download = core::preflight::uncached_download(state.pending_updates(), &cache_files);
+        let (_, installed_delta, _) = state.size_totals();
+        let spaces = match command::get_free_space(&[&cachedir, Path::new("/")], timeout) {
+            Ok(output) => parser::df::parse_df_output(&output),
+            Err(e) => {
+                eprintln!("Warning: Could not check free disk space: {e}");
+                return Ok(true);
+            },
+        };
+
+        match core::preflight::evaluate_free_space(download, installed_delta, &spaces) {
+            SpaceVerdict::Sufficient => return Ok(true),
+            SpaceVerdict::Unknown(reason) => {
+                eprintln!("Warning: Could not check free disk space: {reason}");
+                return Ok(true);
+            },
+            SpaceVerdict::Short(shortages) => state.set_disk_shortages(shortages),
+        }
+        state.pending_action = Some(update_event(mode));
+
+        match terminal::run_tui_for_confirmation(state)? {
+            Some(UIEvent::CleanCache) => {
+                let cmd = planner::cache_cleanup_command(&cachedir);
+                println!("Executing: {}", cmd.join(" "));
+                match command::run_interactive(&cmd) {
+                    Ok(status) if status.success() => {},
+                    Ok(status) => eprintln!("Cache cleanup exited with: {status}"),
+                    Err(e) => eprintln!("Failed to run cache cleanup: {e}"),
+                }
+            },
+            Some(UIEvent::UpdateEntireSystem | UIEvent::UpdateOfficialOnly) => return Ok(true),
+            Some(UIEvent::Quit) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Interrupted,
+                    "User quit during disk space confirmation",
+                ));
+            },
+            _ => return Ok(false), // User cancelled
+        }
+    }
+}
+
+/// Previews the transaction pacman intends with `pacman -Syu --print`.
+///
+/// Runs against a scratch copy of the sync databases, refreshed like
+/// `checkupdates` does, and shows new dependencies, replacements and removals
+/// the package list cannot. If pacman cannot prepare the transaction, its
+/// error is shown instead. If the simulation cannot run at all, the update
+/// proceeds with a warning.
+fn check_and_confirm_transaction(
+    state: &mut ui::app::AppState,
+    all_packages: &[models::package::Package],
+    ignored: &[String],
+    ignored_groups: &[String],
+    config: &models::config::Config,
+    mode: UpdateMode,
+) -> std::io::Result<bool> {
+    let dbpath = &config.pacman.dbpath;
+    let scratch = io::pacman_db::scratch_dbpath();
+    // Start from the databases checkupdates refreshed, else the system ones
+    let checkupdates = io::pacman_db::checkupdates_dbpath();
+    let sources = [checkupdates.as_path(), dbpath.as_path()];
+    if let Err(e) = io::pacman_db::prepare_scratch_dbpath(&scratch, dbpath, &sources) {
+        io::pacman_db::remove_scratch_dbpath(&scratch);
+        eprintln!("Warning: Could not simulate the update: {e}");
+        return Ok(true);
+    }
+
+    let mut plan = planner::create_plan(mode, Vec::new(), ignored.to_vec());
+    plan.ignore_groups = ignored_groups.to_vec();
+    let cmd = plan.simulate_command(&scratch);
+    // `-y` downloads the sync databases, so allow as long as checkupdates
+    let timeout = Duration::from_secs(config.timeouts.checkupdates);
+    let result = command::simulate_transaction(&cmd, timeout);
+    let sync = load_sync_packages(&scratch);
+    io::pacman_db::remove_scratch_dbpath(&scratch);
+
+    let preview = match result {
+        Ok(output) => {
+            let Some(local) = load_local_packages(dbpath) else {
+                eprintln!("Warning: Could not simulate the update: local database unreadable");
+                return Ok(true);
+            };
+            let targets = pacman::parse_print_output(&output);
+            Ok(core::transaction::build_preview(
+                &targets,
+                all_packages,
+                ignored,
+                &local,
+                &sync,
+            ))
+        },
+        Err(command::CommandError::ExecutionFailed(error)) => Err(error),
+        Err(e) => {
+            eprintln!("Warning: Could not simulate the update: {e}");
+            return Ok(true);
+        },
+    };
+
+    state.set_transaction_preview(preview);
+    state.pending_action = Some(update_event(mode));
+
+    match terminal::run_tui_for_confirmation(state)? {
+        Some(UIEvent::UpdateEntireSystem | UIEvent::UpdateOfficialOnly) => Ok(true),
+        Some(UIEvent::Quit) => Err(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            "User quit during transaction preview",
+        )),
+        _ => Ok(false), // User cancelled
+    }
+}
+
+/// Returns the event that starts an update in `mode`.
+fn update_event(mode: UpdateMode) -> UIEvent {
+    match mode {
+        UpdateMode::EntireSystem => UIEvent::UpdateEntireSystem,
+        UpdateMode::OfficialOnly => UIEvent::UpdateOfficialOnly,
+    }
+}
+
+/// Attaches download sizes, installed-size changes, sync repositories and
+/// groups read from the sync databases `checkupdates` refreshed and the local
+/// database under `dbpath`.
+fn annotate_from_databases(packages: &mut [models::package::Package], dbpath: &Path) {
+    let sync = load_sync_packages(&io::pacman_db::checkupdates_dbpath());
+    let local = load_local_packages(dbpath).unwrap_or_default();
+    core::size::annotate_sizes(packages, &sync, &local);
+    core::origin::annotate_origins(packages, &sync);
+}
+
+/// Reads every sync database under `dbpath`, skipping unreadable ones.
+///
+/// Each record is tagged with the repository it was read from.
+fn load_sync_packages(dbpath: &Path) -> Vec<models::package::DbPackage> {
+    io::pacman_db::list_sync_dbs(dbpath)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|(repo, path)| {
+            let records = io::pacman_db::read_sync_db(path).ok()?;
+            Some(
+                records
+                    .iter()
+                    .filter_map(|record| parser::pacman_db::parse_desc(record))
+                    .map(|mut db| {
+                        db.repository = Some(repo.clone());
+                        db
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// Reads every package record from the local pacman database.
+///
+/// Returns `None` if the database cannot be read.
+fn load_local_packages(dbpath: &Path) -> Option<Vec<models::package::DbPackage>> {
+    let records = io::pacman_db::read_local_db(dbpath).ok()?;
+    Some(
+        records
+            .iter()
+            .filter_map(|record| parser::pacman_db::parse_desc(record))
+            .collect(),
+    )
+}
+
 fn execute_update(
     mode: UpdateMode,
     packages: Vec<models::package::Package>,
     ignored: Vec<String>,
+    ignored_groups: Vec<String>,
     config: &models::config::Config,
 ) {
-    let plan = planner::create_plan(mode, packages, ignored);
+    let mut plan = planner::create_plan(mode, packages, ignored);
+    plan.ignore_groups = ignored_groups;
     let cmd = plan.build_command(config);
 
     println!("\n{}", "=".repeat(60));
@@ -1,4 +1,6 @@
+use crate::models::package::{PackageRepository, UpdateKind};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Config {
@@ -6,12 +8,154 @@ pub struct Config {
     pub exclude: ExcludeConfig,
     #[serde(default)]
     pub behavior: BehaviorConfig,
+    #[serde(default)]
+    pub helper: HelperConfig,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    #[serde(default)]
+    pub pacman: PacmanConfig,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ExcludeConfig {
     #[serde(default)]
-    pub permanent: Vec<String>,
+    pub permanent: Vec<PermanentExclude>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_ignore: Vec<AutoIgnoreRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hold: Vec<HoldRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snooze: Vec<SnoozeRule>,
+}
+
+/// A permanent exclusion rule (package name, glob or `re:` regex), optionally
+/// with a note on why it was added and the date it was added.
+///
+/// Written as a plain string when it carries neither, so older configs
+/// (`permanent = ["foo"]`) keep loading and saving unchanged:
+///
+/// ```toml
+/// permanent = [
+///     "*-git",
+///     { name = "linux-lts", note = "pinned for the vendor driver", added = "2026-10-17" },
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "RawPermanentExclude", into = "RawPermanentExclude")]
+pub struct PermanentExclude {
+    pub name: String,
+    pub note: Option<String>,
+    /// UTC date (`YYYY-MM-DD`) the exclusion was added
+    pub added: Option<String>,
+}
+
+impl From<&str> for PermanentExclude {
+    fn from(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            note: None,
+            added: None,
+        }
+    }
+}
+
+impl AsRef<str> for PermanentExclude {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawPermanentExclude {
+    Name(String),
+    Annotated {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        added: Option<String>,
+    },
+}
+
+impl From<RawPermanentExclude> for PermanentExclude {
+    fn from(raw: RawPermanentExclude) -> Self {
+        match raw {
+            RawPermanentExclude::Name(name) => Self::from(name.as_str()),
+            RawPermanentExclude::Annotated { name, note, added } => Self { name, note, added },
+        }
+    }
+}
+
+impl From<PermanentExclude> for RawPermanentExclude {
+    fn from(exclude: PermanentExclude) -> Self {
+        match exclude {
+            PermanentExclude {
+                name,
+                note: None,
+                added: None,
+            } => Self::Name(name),
+            PermanentExclude { name, note, added } => Self::Annotated { name, note, added },
+        }
+    }
+}
+
+/// Ignores a package until `until`, an RFC 3339 UTC timestamp
+/// (`2026-10-24T09:30:00Z`) or a plain date (`2026-10-24`, midnight UTC).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SnoozeRule {
+    pub name: String,
+    pub until: String,
+}
+
+/// Holds a package back while its new version matches `version`.
+///
+/// `name` accepts the same globs and `re:` regexes as permanent exclusions.
+/// `version` is a spec such as `24.1.0`, `24.1.*`, `>=6.7` or `>=24.1, <24.2`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HoldRule {
+    pub name: String,
+    pub version: String,
+}
+
+/// Temporarily ignores updates of a given kind, e.g. pkgrel-only AUR rebuilds.
+///
+/// Matches every repository when `repository` is unset.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AutoIgnoreRule {
+    pub kind: UpdateKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<PackageRepository>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct HelperConfig {
+    /// AUR helper used for scanning and updating. Auto-detected when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<AurHelper>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AurHelper {
+    #[default]
+    Paru,
+    Yay,
+    Pikaur,
+    Aurutils,
+}
+
+impl AurHelper {
+    /// Returns the executable name of the helper (`aur` for aurutils).
+    #[must_use]
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Paru => "paru",
+            Self::Yay => "yay",
+            Self::Pikaur => "pikaur",
+            Self::Aurutils => "aur",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,6 +164,16 @@ pub struct BehaviorConfig {
     pub warn_stale_system: bool,
     #[serde(default)]
     pub extra_args: Vec<String>,
+    /// Days a package stays ignored when snoozed from the TUI
+    #[serde(default = "default_snooze_days")]
+    pub snooze_days: u64,
+    /// Follow dependency conflicts past the first level and offer to hold
+    /// every package that must stay back
+    #[serde(default = "default_transitive_holds")]
+    pub transitive_holds: bool,
+    /// Preview the transaction with `pacman -Su --print` before updating
+    #[serde(default)]
+    pub simulate_transaction: bool,
 }
 
 impl Default for BehaviorConfig {
@@ -27,10 +181,86 @@ impl Default for BehaviorConfig {
         Self {
             warn_stale_system: true,
             extra_args: Vec::new(),
+            snooze_days: default_snooze_days(),
+            transitive_holds: default_transitive_holds(),
+            simulate_transaction: false,
         }
     }
 }
 
+fn default_snooze_days() -> u64 {
+    7
+}
+
 fn default_warn_stale_system() -> bool {
     true
 }
+
+fn default_transitive_holds() -> bool {
+    true
+}
+
+/// Per-command timeouts in seconds. A command still running after its
+/// timeout is killed and reported as a scan failure.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TimeoutConfig {
+    #[serde(default = "default_scan_timeout")]
+    pub checkupdates: u64,
+    #[serde(default = "default_scan_timeout")]
+    pub aur_helper: u64,
+    #[serde(default = "default_query_timeout")]
+    pub pacman_query: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            checkupdates: default_scan_timeout(),
+            aur_helper: default_scan_timeout(),
+            pacman_query: default_query_timeout(),
+        }
+    }
+}
+
+fn default_scan_timeout() -> u64 {
+    120
+}
+
+fn default_query_timeout() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PacmanConfig {
+    /// pacman database directory containing `local/` and `sync/`
+    #[serde(default = "default_dbpath")]
+    pub dbpath: PathBuf,
+    /// Package cache checked for free space and cleaned by `paccache`;
+    /// unset means pacman.conf's first `CacheDir`, else `DEFAULT_CACHEDIR`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cachedir: Option<PathBuf>,
+    /// pacman configuration read for `IgnorePkg`, `IgnoreGroup` and `CacheDir`
+    #[serde(default = "default_conf")]
+    pub conf: PathBuf,
+}
+
+/// pacman's built-in package cache directory.
+pub const DEFAULT_CACHEDIR: &str = "/var/cache/pacman/pkg";
+
+impl Default for PacmanConfig {
+    fn default() -> Self {
+        Self {
+            dbpath: default_dbpath(),
+            cachedir: None,
+            conf: default_conf(),
+        }
+    }
+}
+
+fn default_dbpath() -> PathBuf {
+    PathBuf::from("/var/lib/pacman")
+}
+
+fn default_conf() -> PathBuf {
+    PathBuf::from("/etc/pacman.conf")
+}
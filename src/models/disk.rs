@@ -0,0 +1,16 @@
+/// Free space on the filesystem holding a path, as reported by `df`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilesystemSpace {
+    pub mount_point: String,
+    pub available: u64,
+}
+
+/// A filesystem without enough free space for the pending update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpaceShortage {
+    pub mount_point: String,
+    /// What the space is needed for, e.g. `package cache`
+    pub purpose: String,
+    pub required: u64,
+    pub available: u64,
+}
@@ -1,2 +1,5 @@
 pub mod config;
+pub mod disk;
 pub mod package;
+pub mod pacman_conf;
+pub mod transaction;
@@ -1,13 +1,123 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Package {
     pub name: String,
     pub current_version: Option<String>,
     pub new_version: String,
     pub repository: PackageRepository,
+    /// Compressed download size of the new version, when known
+    pub download_size: Option<u64>,
+    /// Installed size of the new version minus that of the current one
+    pub installed_size_delta: Option<i64>,
+    /// Sync database the new version comes from (`core`, `extra`, `multilib`, ...)
+    pub sync_repo: Option<String>,
+    /// Package groups (`%GROUPS%`) of the new version
+    pub groups: Vec<String>,
+    /// Dependencies (`%DEPENDS%`) of the new version, `None` when unknown (e.g. AUR packages)
+    pub depends: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PackageRepository {
     Official,
     Aur,
 }
+
+/// Kind of version change an update brings, from riskiest to safest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateKind {
+    /// The new version is older than the installed one
+    Downgrade,
+    /// The epoch changed, usually because upstream versioning was reset
+    Epoch,
+    /// The first pkgver segment changed (`1.x` → `2.x`)
+    Major,
+    /// The second pkgver segment changed (`1.2` → `1.3`)
+    Minor,
+    /// A later pkgver segment changed (`1.2.3` → `1.2.4`)
+    Patch,
+    /// Only pkgrel changed: a rebuild of the same upstream version
+    #[serde(rename = "pkgrel")]
+    PkgrelOnly,
+    /// The installed version is unknown
+    Unknown,
+}
+
+/// Backend an update list is scanned from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScanSource {
+    Official,
+    Aur,
+}
+
+impl std::fmt::Display for ScanSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Official => write!(f, "Official"),
+            Self::Aur => write!(f, "AUR"),
+        }
+    }
+}
+
+/// A package record read from a pacman sync or local database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DbPackage {
+    pub name: String,
+    pub version: String,
+    pub depends: Vec<String>,
+    pub provides: Vec<String>,
+    /// Packages this one supersedes on `-Su` (sync databases only)
+    pub replaces: Vec<String>,
+    /// Packages this one cannot be installed alongside
+    pub conflicts: Vec<String>,
+    pub groups: Vec<String>,
+    /// Sync database the record was read from (sync databases only)
+    pub repository: Option<String>,
+    /// Compressed package size (sync databases only)
+    pub download_size: Option<u64>,
+    /// Size on disk once installed
+    pub installed_size: Option<u64>,
+    /// Why the package was installed (local database only)
+    pub install_reason: Option<InstallReason>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallReason {
+    Explicit,
+    Dependency,
+}
+
+/// Details shown in the info pane for a single package.
+///
+/// Fields describe the new version when known; `current_installed_size`
+/// comes from the installed version so the size change can be shown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageDetails {
+    /// Sync repository the record comes from (`pacman -Si` only)
+    pub repository: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub licenses: Vec<String>,
+    pub build_date: Option<String>,
+    pub packager: Option<String>,
+    pub download_size: Option<u64>,
+    pub installed_size: Option<u64>,
+    pub current_installed_size: Option<u64>,
+    pub depends: Vec<String>,
+    pub optdepends: Vec<String>,
+}
+
+/// A package's neighbours in the dependency graph, as `pacman -Qi`/`-Si` lists them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageRelations {
+    pub depends: Vec<String>,
+    /// Installed packages depending on this one (`pacman -Qi` only)
+    pub required_by: Vec<String>,
+    /// `name: reason` entries, as printed by pacman
+    pub optdepends: Vec<String>,
+    pub provides: Vec<String>,
+}
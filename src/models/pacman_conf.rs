@@ -0,0 +1,12 @@
+/// Settings read (never written) from `/etc/pacman.conf` and its `Include`s.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PacmanConf {
+    /// `IgnorePkg` entries; package names or globs
+    pub ignore_pkg: Vec<String>,
+    /// `IgnoreGroup` entries
+    pub ignore_group: Vec<String>,
+    /// Repository sections in file order (`core`, `extra`, ...)
+    pub repositories: Vec<String>,
+    /// `CacheDir` entries in file order
+    pub cache_dirs: Vec<String>,
+}
@@ -0,0 +1,39 @@
+/// A package pacman would install, as printed by `pacman -Syu --print`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionTarget {
+    pub name: String,
+    pub version: String,
+    pub repository: String,
+    /// Bytes still to download; 0 when the package is already cached
+    pub download_size: u64,
+}
+
+/// Why a package is part of the simulated transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionAction {
+    /// An installed package moves to a new version
+    Upgrade { from: String },
+    /// A package that is not installed yet, pulled in as a new dependency
+    Install,
+    /// A package that is not installed yet and supersedes installed ones
+    Replace { replaces: Vec<String> },
+}
+
+/// One package of the simulated transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionEntry {
+    pub target: TransactionTarget,
+    pub action: TransactionAction,
+}
+
+/// The transaction pacman intends to run, compared with the package list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionPreview {
+    pub entries: Vec<TransactionEntry>,
+    /// Installed packages removed because a new package replaces or
+    /// conflicts with them
+    pub removals: Vec<String>,
+    /// Listed repository updates that are not ignored but pacman would not
+    /// upgrade, e.g. because the refreshed sync databases no longer carry them
+    pub skipped: Vec<String>,
+}
@@ -0,0 +1,31 @@
+use crate::models::package::{Package, PackageRepository};
+
+/// Parses `aur repo -u` command output into a list of AUR packages.
+///
+/// Expected format (from `aur vercmp`): `package_name local_version -> remote_version`
+///
+/// Fields may be separated by tabs or spaces.
+#[must_use]
+pub fn parse_aurutils_output(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 4 && parts[2] == "->" {
+                Some(Package {
+                    name: parts[0].to_string(),
+                    current_version: Some(parts[1].to_string()),
+                    new_version: parts[3].to_string(),
+                    repository: PackageRepository::Aur,
+                    download_size: None,
+                    installed_size_delta: None,
+                    sync_repo: None,
+                    groups: Vec::new(),
+                    depends: None,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
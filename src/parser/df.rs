@@ -0,0 +1,25 @@
+use crate::models::disk::FilesystemSpace;
+
+/// Parses `df -B1 --output=target,avail` output, one entry per queried path.
+///
+/// Expected format:
+/// ```text
+/// Mounted on        Avail
+/// /           21474836480
+/// /var         1073741824
+/// ```
+///
+/// Lines whose last column is not a number (including the header) are skipped.
+#[must_use]
+pub fn parse_df_output(output: &str) -> Vec<FilesystemSpace> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (mount_point, available) = line.trim().rsplit_once(char::is_whitespace)?;
+            Some(FilesystemSpace {
+                mount_point: mount_point.trim().to_string(),
+                available: available.parse().ok()?,
+            })
+        })
+        .collect()
+}
@@ -1,3 +1,24 @@
+use crate::models::config::AurHelper;
+use crate::models::package::Package;
+
+pub mod aurutils;
+pub mod df;
 pub mod pacman;
+pub mod pacman_conf;
+pub mod pacman_db;
 pub mod paru;
+pub mod pikaur;
+pub mod srcinfo;
 pub mod toml;
+pub mod yay;
+
+/// Parses the pending update query output of `helper` with its matching parser.
+#[must_use]
+pub fn parse_aur_query(helper: AurHelper, output: &str) -> Vec<Package> {
+    match helper {
+        AurHelper::Paru => paru::parse_paru_output(output),
+        AurHelper::Yay => yay::parse_yay_output(output),
+        AurHelper::Pikaur => pikaur::parse_pikaur_output(output),
+        AurHelper::Aurutils => aurutils::parse_aurutils_output(output),
+    }
+}
@@ -1,4 +1,6 @@
-use crate::models::package::{Package, PackageRepository};
+use crate::models::package::{Package, PackageDetails, PackageRelations, PackageRepository};
+use crate::models::transaction::TransactionTarget;
+use std::collections::HashMap;
 
 /// Parses `checkupdates` command output into a list of packages.
 ///
@@ -15,6 +17,11 @@ pub fn parse_checkupdates_output(output: &str) -> Vec<Package> {
                     current_version: Some(parts[1].to_string()),
                     new_version: parts[3].to_string(),
                     repository: PackageRepository::Official,
+                    download_size: None,
+                    installed_size_delta: None,
+                    sync_repo: None,
+                    groups: Vec::new(),
+                    depends: None,
                 })
             } else {
                 None
@@ -23,6 +30,30 @@ pub fn parse_checkupdates_output(output: &str) -> Vec<Package> {
         .collect()
 }
 
+/// Parses `pacman -Syu --print --print-format '%n %v %r %s'` output.
+///
+/// Expected format: `name version repository download_size`, one target per
+/// line. Other lines, such as `:: Replace foo with extra/bar? [Y/n]` prompts
+/// answered by `--noconfirm`, are skipped.
+#[must_use]
+pub fn parse_print_output(output: &str) -> Vec<TransactionTarget> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let [name, version, repository, size] = line.split_whitespace().collect::<Vec<_>>()[..]
+            else {
+                return None;
+            };
+            Some(TransactionTarget {
+                name: name.to_string(),
+                version: version.to_string(),
+                repository: repository.to_string(),
+                download_size: size.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
 /// Parses `pacman -Qi` output to extract the "Required By" field.
 ///
 /// Expected format:
@@ -35,15 +66,184 @@ pub fn parse_checkupdates_output(output: &str) -> Vec<Package> {
 #[must_use]
 #[allow(dead_code)]
 pub fn parse_required_by(output: &str) -> Vec<String> {
+    parse_package_relations(output).required_by
+}
+
+/// Parses batched `pacman -Qi pkg1 pkg2 …` output into each package's "Required By".
+///
+/// Records are separated by blank lines and keyed by their `Name` field.
+/// Packages pacman could not find have no record, so callers detect them by
+/// their absence from the map.
+#[must_use]
+pub fn parse_required_by_records(output: &str) -> HashMap<String, Vec<String>> {
+    let mut records = HashMap::new();
+    let mut record = String::new();
+
+    for line in output.lines().chain(std::iter::once("")) {
+        if !line.trim().is_empty() {
+            record.push_str(line);
+            record.push('\n');
+            continue;
+        }
+        if let Some(name) = record_name(&record) {
+            records.insert(
+                name.to_string(),
+                parse_package_relations(&record).required_by,
+            );
+        }
+        record.clear();
+    }
+
+    records
+}
+
+/// Returns the `Name` field of a single `pacman -Qi` record.
+fn record_name(record: &str) -> Option<&str> {
+    record.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Name").then(|| value.trim())
+    })
+}
+
+/// Parses `pacman -Qi` or `pacman -Si` output into the package's graph neighbours.
+///
+/// Expected format (as printed with `LC_ALL=C`):
+/// ```text
+/// Provides        : libc.so=6-64
+/// Depends On      : linux-api-headers>=4.10  tzdata  filesystem
+/// Optional Deps   : gd: for memusagestat [installed]
+///                   perl: for mtrace
+/// Required By     : bash  coreutils
+/// ```
+///
+/// Long lists continue on indented lines and `None` means empty. Only the
+/// first record is read when several repositories provide the package.
+#[must_use]
+pub fn parse_package_relations(output: &str) -> PackageRelations {
+    let mut relations = PackageRelations::default();
+    let mut field = String::new();
+    let mut seen_record = false;
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            if seen_record {
+                break;
+            }
+            continue;
+        }
+
+        // Indented lines continue the previous field (e.g. Optional Deps)
+        let value = if line.starts_with(char::is_whitespace) {
+            line.trim()
+        } else if let Some((key, value)) = line.split_once(':') {
+            field = key.trim().to_string();
+            seen_record = true;
+            value.trim()
+        } else {
+            continue;
+        };
+        if value == "None" {
+            continue;
+        }
+
+        let list = || value.split_whitespace().map(String::from);
+        match field.as_str() {
+            "Depends On" => relations.depends.extend(list()),
+            "Required By" => relations.required_by.extend(list()),
+            "Provides" => relations.provides.extend(list()),
+            "Optional Deps" => relations.optdepends.push(value.to_string()),
+            _ => {},
+        }
+    }
+
+    relations
+}
+
+/// Parses `pacman -Si` or `pacman -Qi` output into `PackageDetails`.
+///
+/// Expected format (as printed with `LC_ALL=C`):
+/// ```text
+/// Version         : 2.41-1
+/// Depends On      : linux-api-headers>=4.10  tzdata  filesystem
+/// Optional Deps   : gd: for memusagestat
+///                   perl: for mtrace
+/// Download Size   : 10.46 MiB
+/// ```
+///
+/// List fields are separated by two spaces and `None` means empty. Only the
+/// first record is read when several repositories provide the package.
+#[must_use]
+pub fn parse_package_info(output: &str) -> PackageDetails {
+    let mut details = PackageDetails::default();
+    let mut field = String::new();
+
     for line in output.lines() {
-        let trimmed = line.trim();
-        if let Some(value) = trimmed.strip_prefix("Required By") {
-            let value = value.trim().trim_start_matches(':').trim();
-            if value == "None" || value.is_empty() {
-                return Vec::new();
+        if line.trim().is_empty() {
+            if details.version.is_some() {
+                break;
             }
-            return value.split_whitespace().map(String::from).collect();
+            continue;
+        }
+
+        // Indented lines continue the previous field (e.g. Optional Deps)
+        let value = if line.starts_with(char::is_whitespace) {
+            line.trim()
+        } else if let Some((key, value)) = line.split_once(':') {
+            field = key.trim().to_string();
+            value.trim()
+        } else {
+            continue;
+        };
+        if value == "None" {
+            continue;
+        }
+
+        let text = || Some(value.to_string());
+        let list = || {
+            value
+                .split("  ")
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        match field.as_str() {
+            "Repository" => details.repository = text(),
+            "Version" => details.version = text(),
+            "Description" => details.description = text(),
+            "URL" => details.url = text(),
+            "Licenses" => details.licenses.extend(list()),
+            "Build Date" => details.build_date = text(),
+            "Packager" => details.packager = text(),
+            "Download Size" => details.download_size = parse_size(value),
+            "Installed Size" => details.installed_size = parse_size(value),
+            "Depends On" => details.depends.extend(list()),
+            "Optional Deps" => details.optdepends.push(value.to_string()),
+            _ => {},
         }
     }
-    Vec::new()
+
+    details
+}
+
+/// Parses a pacman size such as `10.46 MiB` into bytes.
+fn parse_size(value: &str) -> Option<u64> {
+    let (number, unit) = value.split_once(' ')?;
+    let multiplier: u64 = match unit.trim() {
+        "B" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return None,
+    };
+
+    // Integer arithmetic on the decimal digits avoids float casts
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let scale = 10u64.checked_pow(u32::try_from(fraction.len()).ok()?)?;
+    let whole: u64 = whole.parse().ok()?;
+    let fraction: u64 = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse().ok()?
+    };
+    Some((whole * scale + fraction) * multiplier / scale)
 }
@@ -0,0 +1,78 @@
+use crate::models::pacman_conf::PacmanConf;
+
+/// pacman refuses deeper `Include` chains; stop there too instead of looping.
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// Parses `pacman.conf` content, following `Include` directives.
+///
+/// `include` receives an `Include` value (a path, possibly a glob) and returns
+/// the contents of the matching files. Included content belongs to the section
+/// the directive appears in.
+///
+/// ```text
+/// [options]
+/// IgnorePkg   = linux linux-headers
+/// IgnoreGroup = gnome
+/// CacheDir    = /var/cache/pacman/pkg/
+///
+/// [core]
+/// Include = /etc/pacman.d/mirrorlist
+/// ```
+///
+/// Only `IgnorePkg`, `IgnoreGroup`, `CacheDir` and repository names are collected;
+/// everything else is skipped.
+#[must_use]
+pub fn parse_pacman_conf<F>(content: &str, mut include: F) -> PacmanConf
+where
+    F: FnMut(&str) -> Vec<String>,
+{
+    let mut conf = PacmanConf::default();
+    let mut section = String::new();
+    parse_into(&mut conf, &mut section, content, &mut include, 0);
+    conf
+}
+
+fn parse_into<F>(
+    conf: &mut PacmanConf,
+    section: &mut String,
+    content: &str,
+    include: &mut F,
+    depth: usize,
+) where
+    F: FnMut(&str) -> Vec<String>,
+{
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            *section = name.trim().to_string();
+            if *section != "options" && !conf.repositories.contains(section) {
+                conf.repositories.push(section.clone());
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue; // Bare flags such as `Color` or `CheckSpace`
+        };
+        let value = value.trim();
+        match (section.as_str(), key.trim()) {
+            (_, "Include") if depth < MAX_INCLUDE_DEPTH => {
+                for included in include(value) {
+                    parse_into(conf, section, &included, include, depth + 1);
+                }
+            },
+            ("options", "IgnorePkg") => conf.ignore_pkg.extend(words(value)),
+            ("options", "IgnoreGroup") => conf.ignore_group.extend(words(value)),
+            ("options", "CacheDir") => conf.cache_dirs.extend(words(value)),
+            _ => {},
+        }
+    }
+}
+
+fn words(value: &str) -> impl Iterator<Item = String> + '_ {
+    value.split_whitespace().map(String::from)
+}
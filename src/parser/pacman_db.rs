@@ -0,0 +1,70 @@
+use crate::models::package::{DbPackage, InstallReason};
+
+/// Parses a pacman database `desc` record into a `DbPackage`.
+///
+/// Expected format: `%FIELD%` headers, each followed by one value per line
+/// and terminated by a blank line:
+///
+/// ```text
+/// %NAME%
+/// glibc
+///
+/// %DEPENDS%
+/// linux-api-headers>=4.10
+/// tzdata
+/// ```
+///
+/// Sync records carry `%CSIZE%`/`%ISIZE%`; local records carry `%SIZE%` and
+/// `%REASON%` (`1` means installed as a dependency, absent means explicit).
+///
+/// Returns `None` if `%NAME%` or `%VERSION%` is missing.
+#[must_use]
+pub fn parse_desc(content: &str) -> Option<DbPackage> {
+    let mut package = DbPackage::default();
+    let mut is_local = false;
+    let mut field = "";
+
+    for line in content.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            field = "";
+            continue;
+        }
+        if line.len() > 2 && line.starts_with('%') && line.ends_with('%') {
+            field = line;
+            continue;
+        }
+
+        match field {
+            "%NAME%" => package.name = line.to_string(),
+            "%VERSION%" => package.version = line.to_string(),
+            "%DEPENDS%" => package.depends.push(line.to_string()),
+            "%PROVIDES%" => package.provides.push(line.to_string()),
+            "%REPLACES%" => package.replaces.push(line.to_string()),
+            "%CONFLICTS%" => package.conflicts.push(line.to_string()),
+            "%GROUPS%" => package.groups.push(line.to_string()),
+            "%CSIZE%" => package.download_size = line.parse().ok(),
+            "%ISIZE%" | "%SIZE%" => package.installed_size = line.parse().ok(),
+            "%INSTALLDATE%" => is_local = true,
+            "%REASON%" => {
+                package.install_reason = Some(if line == "1" {
+                    InstallReason::Dependency
+                } else {
+                    InstallReason::Explicit
+                });
+            },
+            _ => {},
+        }
+    }
+
+    // Local records omit %REASON% for explicitly installed packages
+    if is_local && package.install_reason.is_none() {
+        package.install_reason = Some(InstallReason::Explicit);
+    }
+
+    if package.name.is_empty() || package.version.is_empty() {
+        None
+    } else {
+        Some(package)
+    }
+}
@@ -15,6 +15,11 @@ pub fn parse_paru_output(output: &str) -> Vec<Package> {
                     current_version: Some(parts[1].to_string()),
                     new_version: parts[3].to_string(),
                     repository: PackageRepository::Aur,
+                    download_size: None,
+                    installed_size_delta: None,
+                    sync_repo: None,
+                    groups: Vec::new(),
+                    depends: None,
                 })
             } else {
                 None
@@ -0,0 +1,33 @@
+use crate::models::package::{Package, PackageRepository};
+
+/// Parses `pikaur -Qua` command output into a list of AUR packages.
+///
+/// Expected format: `[aur/]package_name current_version -> new_version`
+///
+/// pikaur interleaves status lines (e.g. `:: Reading AUR package info...`),
+/// so only lines with the `->` separator are accepted.
+#[must_use]
+pub fn parse_pikaur_output(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 4 && parts[2] == "->" {
+                let name = parts[0].strip_prefix("aur/").unwrap_or(parts[0]);
+                Some(Package {
+                    name: name.to_string(),
+                    current_version: Some(parts[1].to_string()),
+                    new_version: parts[3].to_string(),
+                    repository: PackageRepository::Aur,
+                    download_size: None,
+                    installed_size_delta: None,
+                    sync_repo: None,
+                    groups: Vec::new(),
+                    depends: None,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use crate::models::package::PackageDetails;
+
+/// Parses a `.SRCINFO` file into `PackageDetails` for one of its packages.
+///
+/// Expected format: a `pkgbase` section followed by one `pkgname` section
+/// per package, each holding indented `key = value` lines:
+///
+/// ```text
+/// pkgbase = foo-git
+///     pkgdesc = Foo from git
+///     pkgver = r12.abc1234
+///     pkgrel = 1
+///     depends = glibc
+///
+/// pkgname = foo-git
+/// ```
+///
+/// Keys set in the package section replace the `pkgbase` values, and
+/// architecture-specific keys (`depends_x86_64`) are added for the host
+/// architecture. Returns `None` if `pkgname` has no section.
+#[must_use]
+pub fn parse_srcinfo(content: &str, pkgname: &str) -> Option<PackageDetails> {
+    let mut base: Vec<(&str, &str)> = Vec::new();
+    let mut package: Vec<(&str, &str)> = Vec::new();
+    let mut found = false;
+    // None: before pkgbase or inside another package's section
+    let mut section: Option<&mut Vec<(&str, &str)>> = None;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.trim().split_once(" = ") else {
+            continue;
+        };
+        match key {
+            "pkgbase" => section = Some(&mut base),
+            "pkgname" if value == pkgname => {
+                found = true;
+                section = Some(&mut package);
+            },
+            "pkgname" => section = None,
+            _ => {
+                if let Some(entries) = section.as_deref_mut() {
+                    entries.push((key, value));
+                }
+            },
+        }
+    }
+
+    if !found {
+        return None;
+    }
+
+    let arch_suffix = format!("_{}", std::env::consts::ARCH);
+    let values = |key: &str| -> Vec<String> {
+        let arch_key = format!("{key}{arch_suffix}");
+        let matching = |entries: &[(&str, &str)]| -> Vec<String> {
+            entries
+                .iter()
+                .filter(|(k, _)| *k == key || *k == arch_key)
+                .map(|(_, v)| (*v).to_string())
+                .collect()
+        };
+        // Package sections override pkgbase values key by key
+        let overridden = matching(&package);
+        if package.iter().any(|(k, _)| *k == key || *k == arch_key) {
+            overridden
+        } else {
+            matching(&base)
+        }
+    };
+    let single = |key: &str| values(key).into_iter().next();
+
+    let version = single("pkgver").map(|pkgver| {
+        let epoch = single("epoch").map(|e| format!("{e}:")).unwrap_or_default();
+        let pkgrel = single("pkgrel")
+            .map(|r| format!("-{r}"))
+            .unwrap_or_default();
+        format!("{epoch}{pkgver}{pkgrel}")
+    });
+
+    Some(PackageDetails {
+        version,
+        description: single("pkgdesc"),
+        url: single("url"),
+        licenses: values("license"),
+        depends: values("depends"),
+        optdepends: values("optdepends"),
+        ..PackageDetails::default()
+    })
+}
@@ -0,0 +1,29 @@
+use crate::models::package::{Package, PackageRepository};
+
+/// Parses `yay -Qua` command output into a list of AUR packages.
+///
+/// Expected format: `package_name current_version -> new_version`
+#[must_use]
+pub fn parse_yay_output(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 4 && parts[2] == "->" {
+                Some(Package {
+                    name: parts[0].to_string(),
+                    current_version: Some(parts[1].to_string()),
+                    new_version: parts[3].to_string(),
+                    repository: PackageRepository::Aur,
+                    download_size: None,
+                    installed_size_delta: None,
+                    sync_repo: None,
+                    groups: Vec::new(),
+                    depends: None,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
@@ -1,15 +1,109 @@
-use crate::core::dependency::DependencyConflict;
-use crate::models::package::Package;
-use std::collections::HashMap;
+use crate::core::dependency::{
+    self, DependencyConflict, DependencyProblems, HoldNode, LibraryBreakage,
+};
+use crate::core::{filter, size, snooze, time, version};
+use crate::models::config::{
+    AutoIgnoreRule, ExcludeConfig, HoldRule, PermanentExclude, SnoozeRule,
+};
+use crate::models::disk::SpaceShortage;
+use crate::models::package::{
+    Package, PackageDetails, PackageRelations, PackageRepository, ScanSource, UpdateKind,
+};
+use crate::models::pacman_conf::PacmanConf;
+use crate::models::transaction::TransactionPreview;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub enum UIEvent {
     UpdateEntireSystem,
     UpdateOfficialOnly,
     Reload,
+    /// Clean the package cache, then re-check disk space
+    CleanCache,
+    /// A dependency warning fix was applied: re-check, then return to the list
+    RecheckDependencies,
     Quit,
 }
 
+/// Badge label for AUR packages.
+pub const AUR_LABEL: &str = "AUR";
+
+/// Badge label for official packages whose sync repository is unknown.
+pub const OFFICIAL_LABEL: &str = "Official";
+
+/// A whole package group or sync repository ignored for this run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IgnoreSet {
+    Group(String),
+    Repo(String),
+}
+
+/// A branch of the dependency graph browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphSection {
+    Depends,
+    RequiredBy,
+    OptDepends,
+    Provides,
+}
+
+impl GraphSection {
+    pub const ALL: [Self; 4] = [
+        Self::Depends,
+        Self::RequiredBy,
+        Self::OptDepends,
+        Self::Provides,
+    ];
+
+    /// Field name as pacman prints it.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Depends => "Depends On",
+            Self::RequiredBy => "Required By",
+            Self::OptDepends => "Optional Deps",
+            Self::Provides => "Provides",
+        }
+    }
+
+    /// Entries of this branch in `relations`.
+    #[must_use]
+    pub fn entries(self, relations: &PackageRelations) -> &[String] {
+        match self {
+            Self::Depends => &relations.depends,
+            Self::RequiredBy => &relations.required_by,
+            Self::OptDepends => &relations.optdepends,
+            Self::Provides => &relations.provides,
+        }
+    }
+
+    /// Name an entry of this branch refers to, if any.
+    ///
+    /// `glibc>=2.38` → `glibc`, `perl: for mtrace` → `perl`. The name may be a
+    /// provision (`sh`, `libcrypto.so`); `AppState::graph_target` resolves it
+    /// to a package. The focused package's own provisions lead nowhere.
+    #[must_use]
+    pub fn target(self, entry: &str) -> Option<&str> {
+        match self {
+            Self::Depends => Some(dependency::dependency_name(entry)),
+            Self::RequiredBy => Some(entry),
+            Self::OptDepends => entry.split(':').next().map(dependency::dependency_name),
+            Self::Provides => None,
+        }
+    }
+}
+
+/// Dependency graph browser, centred on one package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphView {
+    /// Package the view is centred on
+    pub focus: String,
+    /// Previously focused packages, most recent last
+    pub history: Vec<String>,
+    /// Selected entry, counted across all branches
+    pub cursor: usize,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum LoadingState {
@@ -23,11 +117,26 @@ pub struct AppState {
     pub packages: Vec<PackageItem>,
     pub cursor_position: usize,
     pub show_help: bool,
+    pub show_details: bool,
     pub loading_state: LoadingState,
-    pub loading_message: String,
+    /// Latest progress line per scan source, in the order sources reported
+    pub scan_progress: Vec<(ScanSource, String)>,
     pub scan_warnings: Vec<String>,
+    pub failed_sources: Vec<ScanSource>,
     pub dependency_conflicts: Vec<DependencyConflict>,
+    /// Packages staying behind that will lose a library they need
+    pub library_breakages: Vec<LibraryBreakage>,
+    /// Packages that must be held back along with the ignored ones, as trees
+    /// rooted at the ignored packages (`transitive_holds`)
+    pub hold_trees: Vec<HoldNode>,
     pub show_dependency_warning: bool,
+    /// Non-empty while the disk space warning modal is shown
+    pub disk_shortages: Vec<SpaceShortage>,
+    /// Simulated transaction, or pacman's error if it could not be prepared;
+    /// `Some` while the transaction preview modal is shown
+    pub transaction_preview: Option<Result<TransactionPreview, String>>,
+    /// First visible line of the transaction preview modal
+    pub transaction_scroll: usize,
 
     /// Pending action lifecycle:
     /// 1. Set when Enter/o pressed (before dependency check)
@@ -39,6 +148,58 @@ pub struct AppState {
     /// Cache for `pacman -Qi` reverse dependency queries
     /// Key: package name, Value: list of packages requiring it
     pub reverse_deps_cache: HashMap<String, Vec<String>>,
+
+    /// Cache for info pane lookups (`pacman -Si`/`-Qi` or AUR cache)
+    /// Key: package name, Value: details or the lookup error
+    pub details_cache: HashMap<String, Result<PackageDetails, String>>,
+    /// Packages whose info pane lookup is running on a worker thread
+    pub details_pending: HashSet<String>,
+
+    /// Dependency graph browser; `Some` while it replaces the package list
+    pub graph: Option<GraphView>,
+
+    /// Cache for graph browser lookups (`pacman -Qi`, else `pacman -Si`)
+    /// Key: package name, Value: its neighbours or the lookup error
+    pub relations_cache: HashMap<String, Result<PackageRelations, String>>,
+    /// Packages whose graph lookup is running on a worker thread
+    pub relations_pending: HashSet<String>,
+    /// Installed package names and what they provide (`sh`, `libcrypto.so`),
+    /// mapped to the package providing them; `None` until loaded
+    pub provider_index: Option<HashMap<String, String>>,
+
+    /// Rules applied as temporary ignores whenever packages are (re)loaded
+    pub auto_ignore_rules: Vec<AutoIgnoreRule>,
+
+    /// Permanent exclusion rules (names, globs and `re:` regexes) as saved to config
+    pub permanent_rules: Vec<PermanentExclude>,
+
+    /// Text typed into the exclusion note prompt; `Some` while the prompt is open
+    pub note_input: Option<String>,
+
+    /// Version holds as saved to config
+    pub holds: Vec<HoldRule>,
+
+    /// Held packages that were ignored before their hold and stay ignored
+    /// once it is released
+    pub ignored_before_hold: HashSet<String>,
+
+    /// Exclusion and hold patterns, compiled when packages are loaded
+    patterns: filter::PatternCache,
+
+    /// Unexpired snoozes as saved to config
+    pub snoozes: Vec<SnoozeRule>,
+
+    /// Package groups ignored for this run (passed as `--ignoregroup`)
+    pub ignored_groups: Vec<String>,
+
+    /// Sync repositories whose updates are ignored for this run
+    pub ignored_repos: Vec<String>,
+
+    /// `IgnorePkg`/`IgnoreGroup` read from pacman.conf
+    pub pacman_conf: PacmanConf,
+
+    /// One-shot message shown in the status bar until the next key press
+    pub status_message: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +207,20 @@ pub struct PackageItem {
     pub package: Package,
     pub is_temporarily_ignored: bool,
     pub is_permanently_ignored: bool,
+    /// Permanent exclusion rule covering this package (its name for literal entries)
+    pub permanent_rule: Option<String>,
+    /// Hold keeping this package back at its pending version
+    pub hold: Option<HoldRule>,
+    /// Snooze ignoring this package until it expires
+    pub snooze: Option<SnoozeRule>,
+    /// Ignored group or repository covering this package
+    pub ignore_set: Option<IgnoreSet>,
+    /// pacman.conf directive skipping this package (`IgnorePkg linux`); such
+    /// packages count as permanently ignored and cannot be toggled
+    pub pacman_ignore: Option<String>,
+    pub update_kind: UpdateKind,
+    /// Temporarily ignored by an auto-ignore rule rather than by the user
+    pub is_auto_ignored: bool,
 }
 
 impl AppState {
@@ -56,13 +231,37 @@ impl AppState {
             packages: Vec::new(),
             cursor_position: 0,
             show_help: false,
+            show_details: false,
             loading_state: LoadingState::Scanning,
-            loading_message: "Initializing...".to_string(),
+            scan_progress: Vec::new(),
             scan_warnings: Vec::new(),
+            failed_sources: Vec::new(),
             dependency_conflicts: Vec::new(),
+            library_breakages: Vec::new(),
+            hold_trees: Vec::new(),
             show_dependency_warning: false,
+            disk_shortages: Vec::new(),
+            transaction_preview: None,
+            transaction_scroll: 0,
             pending_action: None,
             reverse_deps_cache: HashMap::new(),
+            details_cache: HashMap::new(),
+            details_pending: HashSet::new(),
+            graph: None,
+            relations_cache: HashMap::new(),
+            relations_pending: HashSet::new(),
+            provider_index: None,
+            auto_ignore_rules: Vec::new(),
+            permanent_rules: Vec::new(),
+            note_input: None,
+            holds: Vec::new(),
+            ignored_before_hold: HashSet::new(),
+            patterns: filter::PatternCache::default(),
+            snoozes: Vec::new(),
+            ignored_groups: Vec::new(),
+            ignored_repos: Vec::new(),
+            pacman_conf: PacmanConf::default(),
+            status_message: None,
         }
     }
 
@@ -70,50 +269,127 @@ impl AppState {
     #[must_use]
     #[allow(dead_code)]
     pub fn new(packages: Vec<Package>, permanent_excludes: &[String]) -> Self {
-        let items = Self::create_package_items(packages, permanent_excludes);
-
-        Self {
-            packages: items,
-            cursor_position: 0,
-            show_help: false,
-            loading_state: LoadingState::Ready,
-            loading_message: String::new(),
-            scan_warnings: Vec::new(),
-            dependency_conflicts: Vec::new(),
-            show_dependency_warning: false,
-            pending_action: None,
-            reverse_deps_cache: HashMap::new(),
-        }
+        let permanent_excludes: Vec<PermanentExclude> = permanent_excludes
+            .iter()
+            .map(|rule| PermanentExclude::from(rule.as_str()))
+            .collect();
+        let mut state = Self::new_loading();
+        state.set_packages(packages, &permanent_excludes);
+        state
     }
 
-    pub fn set_loading_message<S: Into<String>>(&mut self, message: S) {
-        self.loading_message = message.into();
+    /// Replaces the progress line shown for the given scan source.
+    pub fn set_source_progress<S: Into<String>>(&mut self, source: ScanSource, message: S) {
+        let message = message.into();
+        if let Some(entry) = self.scan_progress.iter_mut().find(|(s, _)| *s == source) {
+            entry.1 = message;
+        } else {
+            self.scan_progress.push((source, message));
+        }
     }
 
-    pub fn add_scan_warning<S: Into<String>>(&mut self, warning: S) {
+    /// Records a failed scan source along with its warning message.
+    pub fn add_scan_warning<S: Into<String>>(&mut self, source: ScanSource, warning: S) {
         self.scan_warnings.push(warning.into());
+        if !self.failed_sources.contains(&source) {
+            self.failed_sources.push(source);
+        }
     }
 
-    pub fn set_packages(&mut self, packages: Vec<Package>, permanent_excludes: &[String]) {
-        self.packages = Self::create_package_items(packages, permanent_excludes);
+    pub fn set_packages(
+        &mut self,
+        packages: Vec<Package>,
+        permanent_excludes: &[PermanentExclude],
+    ) {
+        self.permanent_rules = permanent_excludes.to_vec();
+        self.patterns.compile(
+            self.permanent_rules
+                .iter()
+                .map(|rule| rule.name.as_str())
+                .chain(self.holds.iter().map(|hold| hold.name.as_str())),
+        );
+        self.packages = self.create_package_items(packages);
+        // Released holds fall back to the auto-ignore rules
+        self.ignored_before_hold = self
+            .packages
+            .iter()
+            .filter(|item| {
+                item.hold.is_some()
+                    && filter::matches_auto_ignore(
+                        item.update_kind,
+                        item.package.repository,
+                        &self.auto_ignore_rules,
+                    )
+            })
+            .map(|item| item.package.name.clone())
+            .collect();
         self.loading_state = LoadingState::Ready;
         // Clear cache when packages are reloaded as system state may have changed
         self.reverse_deps_cache.clear();
+        self.details_cache.clear();
+        self.details_pending.clear();
     }
 
-    /// Helper to create `PackageItem` list from packages and permanent exclusions
-    fn create_package_items(
-        packages: Vec<Package>,
-        permanent_excludes: &[String],
-    ) -> Vec<PackageItem> {
+    /// Helper to create `PackageItem` list from packages, applying pacman.conf
+    /// ignores, permanent exclusions, holds, snoozes, ignored groups/repositories
+    /// and auto-ignore rules, in that order of precedence
+    ///
+    /// `self.snoozes` must already be limited to unexpired entries.
+    fn create_package_items(&self, packages: Vec<Package>) -> Vec<PackageItem> {
         packages
             .into_iter()
             .map(|pkg| {
-                let is_perm = permanent_excludes.contains(&pkg.name);
+                let pacman_ignore = pacman_conf_ignore(&pkg, &self.pacman_conf);
+                let permanent_rule = if pacman_ignore.is_some() {
+                    None
+                } else {
+                    filter::matching_rule(&pkg.name, &self.permanent_rules, &self.patterns)
+                        .map(String::from)
+                };
+                let is_perm = pacman_ignore.is_some() || permanent_rule.is_some();
+                let update_kind =
+                    version::classify_update(pkg.current_version.as_deref(), &pkg.new_version);
+                let hold = if is_perm {
+                    None
+                } else {
+                    filter::matching_hold(&pkg, &self.holds, &self.patterns).cloned()
+                };
+                let snooze = if is_perm || hold.is_some() {
+                    None
+                } else {
+                    self.snoozes
+                        .iter()
+                        .find(|snooze| snooze.name == pkg.name)
+                        .cloned()
+                };
+                let ignore_set = if is_perm || hold.is_some() || snooze.is_some() {
+                    None
+                } else {
+                    matching_ignore_set(&pkg, &self.ignored_groups, &self.ignored_repos)
+                };
+                let is_auto = !is_perm
+                    && hold.is_none()
+                    && snooze.is_none()
+                    && ignore_set.is_none()
+                    && filter::matches_auto_ignore(
+                        update_kind,
+                        pkg.repository,
+                        &self.auto_ignore_rules,
+                    );
                 PackageItem {
+                    is_temporarily_ignored: is_auto
+                        || hold.is_some()
+                        || snooze.is_some()
+                        || ignore_set.is_some(),
                     package: pkg,
-                    is_temporarily_ignored: false,
                     is_permanently_ignored: is_perm,
+                    pacman_ignore,
+                    permanent_rule,
+                    hold,
+                    snooze,
+                    ignore_set,
+                    update_kind,
+                    is_auto_ignored: is_auto,
                 }
             })
             .collect()
@@ -140,63 +416,590 @@ impl AppState {
         }
     }
 
+    /// Toggles the temporary ignore of the current package.
+    ///
+    /// Held, snoozed and group/repository-ignored packages are left alone,
+    /// since their rule would keep ignoring them; the status bar points to
+    /// `h`, `s`, `g` or `R` instead.
     pub fn toggle_current_package(&mut self) {
+        if let Some(item) = self.packages.get(self.cursor_position) {
+            let name = &item.package.name;
+            if let Some(hold) = &item.hold {
+                self.status_message = Some(format!(
+                    "{name} is held at {}; press h to release it",
+                    hold.version
+                ));
+                return;
+            }
+            if let Some(snooze) = &item.snooze {
+                self.status_message = Some(format!(
+                    "{name} is snoozed until {}; press s to wake it",
+                    snooze.until
+                ));
+                return;
+            }
+            if let Some(ignore_set) = &item.ignore_set {
+                self.status_message = Some(match ignore_set {
+                    IgnoreSet::Group(group) => {
+                        format!("{name} is ignored with group {group}; press g to change it")
+                    },
+                    IgnoreSet::Repo(repo) => {
+                        format!("{name} is ignored with repository {repo}; press R to change it")
+                    },
+                });
+                return;
+            }
+        }
         if let Some(item) = self.packages.get_mut(self.cursor_position)
             && !item.is_permanently_ignored
         {
             item.is_temporarily_ignored = !item.is_temporarily_ignored;
+            item.is_auto_ignored = false;
             // Clear cache as ignore status affects conflict detection
             self.reverse_deps_cache.clear();
         }
     }
 
+    /// Handles `p`: opens the note prompt before excluding the current package,
+    /// or removes/explains an existing exclusion right away.
+    pub fn begin_permanent_ignore(&mut self) {
+        if self.explain_pacman_ignore() {
+            return;
+        }
+        match self.packages.get(self.cursor_position) {
+            Some(item) if item.permanent_rule.is_none() => self.note_input = Some(String::new()),
+            Some(_) => self.toggle_permanent_ignore(),
+            None => {},
+        }
+    }
+
+    /// Closes the note prompt and excludes the current package with the typed
+    /// note (if any), dated with the UTC day of `now` (Unix seconds).
+    pub fn confirm_permanent_note(&mut self, now: u64) {
+        let Some(input) = self.note_input.take() else {
+            return;
+        };
+        let note = input.trim();
+        let note = (!note.is_empty()).then(|| note.to_string());
+        self.toggle_permanent_entry(note, Some(time::format_date(now)));
+    }
+
+    /// Closes the note prompt without excluding anything.
+    pub fn cancel_permanent_note(&mut self) {
+        self.note_input = None;
+    }
+
+    /// Explains in the status bar when pacman.conf skips the current package.
+    ///
+    /// Returns true if it does, in which case the package must not be toggled.
+    fn explain_pacman_ignore(&mut self) -> bool {
+        let Some(item) = self.packages.get(self.cursor_position) else {
+            return false;
+        };
+        let Some(directive) = &item.pacman_ignore else {
+            return false;
+        };
+        self.status_message = Some(format!(
+            "{} is skipped by pacman.conf ({directive}); edit pacman.conf to change it",
+            item.package.name
+        ));
+        true
+    }
+
+    /// Adds or removes a literal permanent exclusion for the current package.
+    ///
+    /// Packages covered by a pattern are left alone; the status bar explains
+    /// which pattern excludes them instead.
     pub fn toggle_permanent_ignore(&mut self) {
-        if let Some(item) = self.packages.get_mut(self.cursor_position) {
-            item.is_permanently_ignored = !item.is_permanently_ignored;
-            if item.is_permanently_ignored {
-                item.is_temporarily_ignored = false;
+        self.toggle_permanent_entry(None, None);
+    }
+
+    fn toggle_permanent_entry(&mut self, note: Option<String>, added: Option<String>) {
+        if self.explain_pacman_ignore() {
+            return;
+        }
+        let Some(item) = self.packages.get_mut(self.cursor_position) else {
+            return;
+        };
+        let name = item.package.name.clone();
+
+        if let Some(rule) = item.permanent_rule.as_ref().filter(|rule| **rule != name) {
+            self.status_message = Some(format!(
+                "{name} is excluded by pattern '{rule}' (edit config.toml to change it)"
+            ));
+            return;
+        }
+
+        if item.is_permanently_ignored {
+            self.permanent_rules.retain(|rule| rule.name != name);
+        } else {
+            self.permanent_rules.push(PermanentExclude {
+                name: name.clone(),
+                note,
+                added,
+            });
+        }
+
+        // A pattern may still cover the package once its literal entry is gone
+        item.permanent_rule =
+            filter::matching_rule(&name, &self.permanent_rules, &self.patterns).map(String::from);
+        item.is_permanently_ignored = item.permanent_rule.is_some();
+        if item.is_permanently_ignored {
+            item.is_temporarily_ignored = false;
+            item.is_auto_ignored = false;
+        }
+        if let Some(rule) = item.permanent_rule.as_ref().filter(|rule| **rule != name) {
+            self.status_message = Some(format!("{name} is still excluded by pattern '{rule}'"));
+        }
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
+    }
+
+    /// Holds the current package at its pending version, or releases its hold.
+    ///
+    /// New holds cover the pending `[epoch:]pkgver`, so rebuilds of the same
+    /// version stay held. Holds whose name is a pattern are only explained.
+    pub fn toggle_hold(&mut self) {
+        if self.explain_pacman_ignore() {
+            return;
+        }
+        let Some(item) = self.packages.get_mut(self.cursor_position) else {
+            return;
+        };
+        let name = item.package.name.clone();
+
+        if item.is_permanently_ignored {
+            self.status_message = Some(format!("{name} is already permanently excluded"));
+            return;
+        }
+
+        if let Some(hold) = &item.hold {
+            if hold.name != name {
+                self.status_message = Some(format!(
+                    "{name} is held by '{}' at {} (edit config.toml to change it)",
+                    hold.name, hold.version
+                ));
+                return;
             }
-            // Clear cache as ignore status affects conflict detection
-            self.reverse_deps_cache.clear();
+            let new_version = &item.package.new_version;
+            self.holds.retain(|hold| {
+                hold.name != name || !version::matches_spec(new_version, &hold.version)
+            });
+        } else {
+            let held_version = version::strip_pkgrel(&item.package.new_version).to_string();
+            self.status_message = Some(format!(
+                "Holding {name} until a version other than {held_version} appears"
+            ));
+            if item.is_temporarily_ignored && item.snooze.is_none() && item.ignore_set.is_none() {
+                self.ignored_before_hold.insert(name.clone());
+            }
+            self.snoozes.retain(|snooze| snooze.name != name);
+            self.holds.push(HoldRule {
+                name: name.clone(),
+                version: held_version,
+            });
+        }
+
+        item.hold = filter::matching_hold(&item.package, &self.holds, &self.patterns).cloned();
+        item.snooze = None;
+        if item.hold.is_some() {
+            item.ignore_set = None;
+            item.is_temporarily_ignored = true;
+        } else {
+            // Back to what the package was before the hold, plus any ignored
+            // group or repository that now covers it
+            item.ignore_set =
+                matching_ignore_set(&item.package, &self.ignored_groups, &self.ignored_repos);
+            item.is_temporarily_ignored =
+                self.ignored_before_hold.remove(&name) || item.ignore_set.is_some();
+        }
+        item.is_auto_ignored = false;
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
+    }
+
+    /// Snoozes the current package for `days` days from `now` (Unix seconds),
+    /// or wakes it if already snoozed.
+    ///
+    /// Held or permanently excluded packages are left alone.
+    pub fn toggle_snooze(&mut self, now: u64, days: u64) {
+        if self.explain_pacman_ignore() {
+            return;
+        }
+        let Some(item) = self.packages.get_mut(self.cursor_position) else {
+            return;
+        };
+        let name = item.package.name.clone();
+
+        if item.is_permanently_ignored {
+            self.status_message = Some(format!("{name} is already permanently excluded"));
+            return;
+        }
+        if item.hold.is_some() {
+            self.status_message = Some(format!("{name} is already held"));
+            return;
+        }
+
+        if item.snooze.is_some() {
+            self.snoozes.retain(|snooze| snooze.name != name);
+            item.snooze = None;
+            item.is_temporarily_ignored = false;
+        } else {
+            let until = snooze::expiry_after(now, days);
+            self.status_message = Some(format!("Snoozing {name} until {until}"));
+            let snooze = SnoozeRule { name, until };
+            self.snoozes.push(snooze.clone());
+            item.snooze = Some(snooze);
+            item.is_temporarily_ignored = true;
+        }
+        item.is_auto_ignored = false;
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
+    }
+
+    /// Ignores every update in a group of the current package for this run.
+    ///
+    /// Packages in several groups step through them on each press (first
+    /// group, second group, ..., none), so any one of them can be picked.
+    pub fn toggle_group_ignore(&mut self) {
+        let Some(item) = self.packages.get(self.cursor_position) else {
+            return;
+        };
+        let groups = item.package.groups.clone();
+        if groups.is_empty() {
+            self.status_message =
+                Some(format!("{} is not in any package group", item.package.name));
+            return;
+        }
+
+        let next = groups
+            .iter()
+            .position(|group| self.ignored_groups.contains(group))
+            .map_or(0, |ignored| ignored + 1);
+        self.ignored_groups.retain(|group| !groups.contains(group));
+        self.status_message = Some(if let Some(group) = groups.get(next) {
+            self.ignored_groups.push(group.clone());
+            format!("Ignoring group {group} for this run")
+        } else {
+            format!("No longer ignoring {}", groups.join(", "))
+        });
+        self.refresh_ignore_sets();
+    }
+
+    /// Ignores every update from the current package's sync repository for
+    /// this run, or stops ignoring it.
+    pub fn toggle_repo_ignore(&mut self) {
+        let Some(item) = self.packages.get(self.cursor_position) else {
+            return;
+        };
+        let Some(repo) = item.package.sync_repo.clone() else {
+            self.status_message = Some(format!(
+                "{} does not come from a sync repository",
+                item.package.name
+            ));
+            return;
+        };
+
+        if self.ignored_repos.contains(&repo) {
+            self.status_message = Some(format!("No longer ignoring repository {repo}"));
+            self.ignored_repos.retain(|ignored| *ignored != repo);
+        } else {
+            self.status_message = Some(format!("Ignoring repository {repo} for this run"));
+            self.ignored_repos.push(repo);
         }
+        self.refresh_ignore_sets();
+    }
+
+    /// Re-applies ignored groups/repositories, touching only packages whose
+    /// coverage changed so manual toggles elsewhere are kept.
+    fn refresh_ignore_sets(&mut self) {
+        for item in &mut self.packages {
+            if item.is_permanently_ignored || item.hold.is_some() || item.snooze.is_some() {
+                continue;
+            }
+            let ignore_set =
+                matching_ignore_set(&item.package, &self.ignored_groups, &self.ignored_repos);
+            if ignore_set != item.ignore_set {
+                item.is_temporarily_ignored = ignore_set.is_some();
+                item.is_auto_ignored = false;
+                item.ignore_set = ignore_set;
+            }
+        }
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
     }
 
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
     }
 
-    /// Returns a list of all ignored package names (temporary + permanent).
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+    }
+
+    /// Returns the package under the cursor.
     #[must_use]
-    pub fn get_ignored_packages(&self) -> Vec<String> {
+    pub fn current_package(&self) -> Option<&Package> {
         self.packages
+            .get(self.cursor_position)
+            .map(|item| &item.package)
+    }
+
+    /// Returns the cached info pane lookup for the package under the cursor.
+    #[must_use]
+    pub fn current_details(&self) -> Option<&Result<PackageDetails, String>> {
+        self.current_package()
+            .and_then(|pkg| self.details_cache.get(&pkg.name))
+    }
+
+    /// Claims the info pane lookup for the package under the cursor.
+    ///
+    /// Returns the package to look up unless its details are cached or already
+    /// being fetched; the caller reports back with `finish_details`. Failed
+    /// lookups are cached too, so they are not retried on every frame.
+    pub fn request_current_details(&mut self) -> Option<Package> {
+        let pkg = self.current_package()?;
+        if self.details_cache.contains_key(&pkg.name) || self.details_pending.contains(&pkg.name) {
+            return None;
+        }
+        let pkg = pkg.clone();
+        self.details_pending.insert(pkg.name.clone());
+        Some(pkg)
+    }
+
+    /// Stores the result of an info pane lookup.
+    ///
+    /// A repository reported by the lookup fills in an unknown `sync_repo`.
+    pub fn finish_details(&mut self, name: String, details: Result<PackageDetails, String>) {
+        self.details_pending.remove(&name);
+        if let Ok(PackageDetails {
+            repository: Some(repo),
+            ..
+        }) = &details
+            && let Some(item) = self
+                .packages
+                .iter_mut()
+                .find(|item| item.package.name == name && item.package.sync_repo.is_none())
+        {
+            item.package.sync_repo = Some(repo.clone());
+        }
+        self.details_cache.insert(name, details);
+    }
+
+    /// Opens the dependency graph browser on the package under the cursor.
+    pub fn open_graph(&mut self) {
+        if let Some(pkg) = self.current_package() {
+            self.graph = Some(GraphView {
+                focus: pkg.name.clone(),
+                history: Vec::new(),
+                cursor: 0,
+            });
+        }
+    }
+
+    /// Closes the dependency graph browser, returning to the package list.
+    pub fn close_graph(&mut self) {
+        self.graph = None;
+    }
+
+    /// Returns the cached neighbours of the focused package, if loaded.
+    #[must_use]
+    pub fn graph_relations(&self) -> Option<&Result<PackageRelations, String>> {
+        self.graph
+            .as_ref()
+            .and_then(|graph| self.relations_cache.get(&graph.focus))
+    }
+
+    /// Returns the focused package's neighbours in display order, with their branch.
+    #[must_use]
+    pub fn graph_entries(&self) -> Vec<(GraphSection, &str)> {
+        let Some(Ok(relations)) = self.graph_relations() else {
+            return Vec::new();
+        };
+        GraphSection::ALL
             .iter()
-            .filter(|item| item.is_temporarily_ignored || item.is_permanently_ignored)
-            .map(|item| item.package.name.clone())
+            .flat_map(|&section| {
+                section
+                    .entries(relations)
+                    .iter()
+                    .map(move |entry| (section, entry.as_str()))
+            })
             .collect()
     }
 
-    /// Returns a list of permanently ignored package names.
+    /// Marks the focused package's neighbours as being fetched.
+    ///
+    /// Returns the package to look up unless its neighbours are cached or
+    /// already being fetched; the caller reports back with `finish_relations`.
+    /// Failed lookups are cached too, so they are not retried on every frame.
+    pub fn request_graph_relations(&mut self) -> Option<String> {
+        let focus = &self.graph.as_ref()?.focus;
+        if self.relations_cache.contains_key(focus) || self.relations_pending.contains(focus) {
+            return None;
+        }
+        let focus = focus.clone();
+        self.relations_pending.insert(focus.clone());
+        Some(focus)
+    }
+
+    /// Stores the result of a graph browser lookup.
+    pub fn finish_relations(&mut self, name: String, relations: Result<PackageRelations, String>) {
+        self.relations_pending.remove(&name);
+        self.relations_cache.insert(name, relations);
+    }
+
+    /// Package a graph entry leads to, with provisions resolved to the
+    /// installed package providing them (`sh` → `bash`).
+    ///
+    /// Names nothing installed provides are kept as they are, since they may
+    /// be sync packages. Nothing is navigable until `provider_index` is loaded.
     #[must_use]
-    pub fn get_permanent_excludes(&self) -> Vec<String> {
+    pub fn graph_target<'a>(&'a self, section: GraphSection, entry: &'a str) -> Option<&'a str> {
+        let name = section.target(entry)?;
+        let index = self.provider_index.as_ref()?;
+        Some(index.get(name).map_or(name, String::as_str))
+    }
+
+    pub fn graph_cursor_up(&mut self) {
+        if let Some(graph) = self.graph.as_mut() {
+            graph.cursor = graph.cursor.saturating_sub(1);
+        }
+    }
+
+    pub fn graph_cursor_down(&mut self) {
+        let count = self.graph_entries().len();
+        if let Some(graph) = self.graph.as_mut()
+            && graph.cursor + 1 < count
+        {
+            graph.cursor += 1;
+        }
+    }
+
+    /// Recentres the graph on the selected neighbour, if it is a package.
+    pub fn graph_enter(&mut self) {
+        let target = self
+            .graph
+            .as_ref()
+            .and_then(|graph| self.graph_entries().get(graph.cursor).copied())
+            .and_then(|(section, entry)| self.graph_target(section, entry))
+            .map(String::from);
+        let (Some(target), Some(graph)) = (target, self.graph.as_mut()) else {
+            return;
+        };
+        if target != graph.focus {
+            graph
+                .history
+                .push(std::mem::replace(&mut graph.focus, target));
+            graph.cursor = 0;
+        }
+    }
+
+    /// Recentres the graph on the previously focused package.
+    pub fn graph_back(&mut self) {
+        if let Some(graph) = self.graph.as_mut()
+            && let Some(previous) = graph.history.pop()
+        {
+            graph.focus = previous;
+            graph.cursor = 0;
+        }
+    }
+
+    /// Returns the pending update for `name`, if it is in the update set.
+    #[must_use]
+    pub fn find_item(&self, name: &str) -> Option<&PackageItem> {
+        self.packages.iter().find(|item| item.package.name == name)
+    }
+
+    /// Returns a list of all ignored package names (temporary + permanent).
+    #[must_use]
+    pub fn get_ignored_packages(&self) -> Vec<String> {
         self.packages
             .iter()
-            .filter(|item| item.is_permanently_ignored)
+            .filter(|item| item.is_temporarily_ignored || item.is_permanently_ignored)
             .map(|item| item.package.name.clone())
             .collect()
     }
 
+    /// Seeds the exclusion rules of a new scan from config.
+    ///
+    /// Snoozes that expired by `now` (Unix seconds) are dropped and reported
+    /// in the status bar; invalid permanent patterns become scan warnings.
+    pub fn load_exclusions(&mut self, exclude: &ExcludeConfig, now: u64) {
+        self.auto_ignore_rules.clone_from(&exclude.auto_ignore);
+        self.permanent_rules.clone_from(&exclude.permanent);
+        self.holds.clone_from(&exclude.hold);
+        let (active, expired) = snooze::partition_snoozes(&exclude.snooze, now);
+        self.snoozes = active;
+        if !expired.is_empty() {
+            let names: Vec<&str> = expired.iter().map(|s| s.name.as_str()).collect();
+            self.status_message = Some(format!("Snooze expired: {}", names.join(", ")));
+        }
+        for (rule, _) in filter::invalid_patterns(&exclude.permanent) {
+            self.scan_warnings
+                .push(format!("invalid exclude pattern '{rule}'"));
+        }
+    }
+
+    /// Returns `exclude` with this run's permanent exclusions, holds and
+    /// snoozes, or `None` if none of them changed.
+    ///
+    /// Expired snoozes were dropped by `load_exclusions`, so this also prunes them.
+    #[must_use]
+    pub fn changed_exclusions(&self, exclude: &ExcludeConfig) -> Option<ExcludeConfig> {
+        let permanent = self.get_permanent_excludes();
+        let hold = self.get_holds();
+        let snooze = self.get_snoozes();
+        if permanent == exclude.permanent && hold == exclude.hold && snooze == exclude.snooze {
+            return None;
+        }
+        Some(ExcludeConfig {
+            permanent,
+            hold,
+            snooze,
+            ..exclude.clone()
+        })
+    }
+
+    /// Returns the permanent exclusion rules to save, patterns included.
+    ///
+    /// Entries for packages without a pending update are kept as well.
+    #[must_use]
+    pub fn get_permanent_excludes(&self) -> Vec<PermanentExclude> {
+        self.permanent_rules.clone()
+    }
+
+    /// Returns the permanent exclusion entry covering `item`, with its note and date.
+    #[must_use]
+    pub fn permanent_entry(&self, item: &PackageItem) -> Option<&PermanentExclude> {
+        let rule = item.permanent_rule.as_deref()?;
+        self.permanent_rules.iter().find(|entry| entry.name == rule)
+    }
+
+    /// Returns the version holds to save.
+    #[must_use]
+    pub fn get_holds(&self) -> Vec<HoldRule> {
+        self.holds.clone()
+    }
+
+    /// Returns the package groups to pass as `--ignoregroup`.
+    #[must_use]
+    pub fn get_ignored_groups(&self) -> Vec<String> {
+        self.ignored_groups.clone()
+    }
+
+    /// Returns the unexpired snoozes to save.
+    #[must_use]
+    pub fn get_snoozes(&self) -> Vec<SnoozeRule> {
+        self.snoozes.clone()
+    }
+
     /// Returns package statistics: (`official_count`, `aur_count`, `ignored_count`).
     #[must_use]
     pub fn stats(&self) -> (usize, usize, usize) {
         let official = self
             .packages
             .iter()
-            .filter(|p| {
-                matches!(
-                    p.package.repository,
-                    crate::models::package::PackageRepository::Official
-                )
-            })
+            .filter(|p| matches!(p.package.repository, PackageRepository::Official))
             .count();
         let aur = self.packages.len() - official;
         let ignored = self
@@ -207,12 +1010,53 @@ impl AppState {
         (official, aur, ignored)
     }
 
+    /// Returns the number of updates per repository label (see `repository_label`).
+    ///
+    /// Repositories follow pacman.conf order, unknown ones come next sorted by
+    /// name, and AUR comes last.
+    #[must_use]
+    pub fn repo_stats(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for item in &self.packages {
+            let label = repository_label(&item.package);
+            match counts.iter_mut().find(|(repo, _)| repo == label) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((label.to_string(), 1)),
+            }
+        }
+
+        let repositories = &self.pacman_conf.repositories;
+        counts.sort_by_key(|(repo, _)| {
+            let position = repositories.iter().position(|known| known == repo);
+            (
+                repo == AUR_LABEL,
+                position.is_none(),
+                position,
+                repo.clone(),
+            )
+        });
+        counts
+    }
+
+    /// Returns size totals for the packages that will be upgraded (ignored ones excluded):
+    /// (`download_bytes`, `installed_delta_bytes`, `packages_without_sizes`).
+    #[must_use]
+    pub fn size_totals(&self) -> (u64, i64, usize) {
+        size::size_totals(self.pending_updates())
+    }
+
+    /// Returns the packages that will actually be upgraded.
+    pub fn pending_updates(&self) -> impl Iterator<Item = &Package> {
+        self.packages
+            .iter()
+            .filter(|p| !p.is_temporarily_ignored && !p.is_permanently_ignored)
+            .map(|p| &p.package)
+    }
+
     /// Returns true if official scan has failed
     #[must_use]
     pub fn has_official_scan_failed(&self) -> bool {
-        self.scan_warnings
-            .iter()
-            .any(|w| w.contains(crate::io::terminal::OFFICIAL_SCAN_FAILURE_MARKER))
+        self.failed_sources.contains(&ScanSource::Official)
     }
 
     /// Returns true if state is ready (not loading/scanning)
@@ -237,11 +1081,236 @@ impl AppState {
         }
     }
 
+    /// Sets library breakages and shows the dependency warning modal
+    pub fn set_library_breakages(&mut self, breakages: Vec<LibraryBreakage>) {
+        self.library_breakages = breakages;
+        if !self.library_breakages.is_empty() {
+            self.show_dependency_warning = true;
+        }
+    }
+
+    /// Shows the dependency warning modal for `problems`
+    pub fn set_dependency_problems(&mut self, problems: DependencyProblems) {
+        self.set_dependency_conflicts(problems.conflicts);
+        self.set_library_breakages(problems.breakages);
+        self.hold_trees = problems.hold_trees;
+        self.show_dependency_warning = true;
+    }
+
+    /// Clears the dependency warning after a fix and adds the recheck
+    /// `summary` to the fix's status message
+    pub fn finish_dependency_recheck(&mut self, summary: &str) {
+        self.dependency_conflicts.clear();
+        self.library_breakages.clear();
+        self.hold_trees.clear();
+        self.status_message = Some(match self.status_message.take() {
+            Some(fix) => format!("{fix}; {summary}"),
+            None => summary.to_string(),
+        });
+    }
+
+    /// Sets disk space shortages; the warning modal shows while any remain
+    pub fn set_disk_shortages(&mut self, shortages: Vec<SpaceShortage>) {
+        self.disk_shortages = shortages;
+    }
+
+    /// Returns true if the disk space warning modal is shown
+    #[must_use]
+    pub fn has_disk_shortages(&self) -> bool {
+        !self.disk_shortages.is_empty()
+    }
+
+    /// Shows the transaction preview modal, scrolled to the top
+    pub fn set_transaction_preview(&mut self, preview: Result<TransactionPreview, String>) {
+        self.transaction_preview = Some(preview);
+        self.transaction_scroll = 0;
+    }
+
+    /// Returns true if the transaction preview modal is shown
+    #[must_use]
+    pub fn has_transaction_preview(&self) -> bool {
+        self.transaction_preview.is_some()
+    }
+
+    /// Scrolls the transaction preview down one line, up to its last item
+    pub fn transaction_scroll_down(&mut self) {
+        let items = match &self.transaction_preview {
+            Some(Ok(preview)) => {
+                preview.entries.len() + preview.removals.len() + preview.skipped.len()
+            },
+            Some(Err(error)) => error.lines().count(),
+            None => 0,
+        };
+        if self.transaction_scroll < items {
+            self.transaction_scroll += 1;
+        }
+    }
+
+    /// Scrolls the transaction preview up one line
+    pub fn transaction_scroll_up(&mut self) {
+        self.transaction_scroll = self.transaction_scroll.saturating_sub(1);
+    }
+
     /// Checks if there are any dependency conflicts
     #[must_use]
     #[allow(dead_code)]
     pub fn has_conflicts(&self) -> bool {
-        !self.dependency_conflicts.is_empty()
+        !self.dependency_conflicts.is_empty() || !self.library_breakages.is_empty()
+    }
+
+    /// Fix offered by the dependency warning: also ignores the updates that
+    /// need an ignored package, and those that take a library away from a
+    /// package staying behind.
+    pub fn ignore_conflict_dependents(&mut self) {
+        let mut dependents: Vec<&str> = Vec::new();
+        for conflict in &self.dependency_conflicts {
+            dependents.extend(conflict.required_by.iter().map(String::as_str));
+            dependents.extend(conflict.violations.iter().map(|v| v.package.as_str()));
+        }
+        dependents.extend(self.library_breakages.iter().map(|b| b.provider.as_str()));
+
+        let mut ignored = Vec::new();
+        for item in &mut self.packages {
+            if dependents.contains(&item.package.name.as_str())
+                && !item.is_temporarily_ignored
+                && !item.is_permanently_ignored
+            {
+                item.is_temporarily_ignored = true;
+                item.is_auto_ignored = false;
+                ignored.push(item.package.name.clone());
+            }
+        }
+
+        self.status_message = Some(if ignored.is_empty() {
+            "No further updates to ignore".to_string()
+        } else {
+            format!("Also ignoring {}", ignored.join(", "))
+        });
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
+    }
+
+    /// Fix offered by the dependency warning: updates the ignored packages
+    /// behind it after all.
+    ///
+    /// Their holds and snoozes are released. Permanent exclusions, pacman.conf
+    /// and group/repository ignores are kept and reported, since they would
+    /// still apply to the update.
+    pub fn unignore_conflicting(&mut self) {
+        let mut names: Vec<&str> = self
+            .dependency_conflicts
+            .iter()
+            .map(|c| c.ignored_package.as_str())
+            .collect();
+        names.extend(self.library_breakages.iter().map(|b| b.package.as_str()));
+
+        let mut released = Vec::new();
+        let mut kept = Vec::new();
+        for item in &mut self.packages {
+            let name = item.package.name.as_str();
+            if !names.contains(&name)
+                || !(item.is_temporarily_ignored || item.is_permanently_ignored)
+            {
+                continue;
+            }
+            if item.is_permanently_ignored || item.ignore_set.is_some() {
+                kept.push(name.to_string());
+                continue;
+            }
+            if item.hold.as_ref().is_some_and(|hold| hold.name != name) {
+                // Pattern holds cover other packages too; leave them to config.toml
+                kept.push(name.to_string());
+                continue;
+            }
+            self.holds.retain(|hold| hold.name != name);
+            self.snoozes.retain(|snooze| snooze.name != name);
+            self.ignored_before_hold.remove(name);
+            item.hold = None;
+            item.snooze = None;
+            item.is_temporarily_ignored = false;
+            item.is_auto_ignored = false;
+            released.push(name.to_string());
+        }
+
+        let mut message = if released.is_empty() {
+            "Nothing to un-ignore".to_string()
+        } else {
+            format!("Updating {}", released.join(", "))
+        };
+        if !kept.is_empty() {
+            message = format!("{message} ({} still excluded)", kept.join(", "));
+        }
+        self.status_message = Some(message);
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
+    }
+
+    /// Fix offered by the dependency warning: ignores every package in the
+    /// hold trees for this run.
+    ///
+    /// Unlike `h`, nothing is saved to config.toml; the trees can change with
+    /// the next sync, so the packages are offered again on the next run.
+    pub fn hold_all_dependents(&mut self) {
+        let names = dependency::held_dependents(&self.hold_trees);
+
+        let mut held = Vec::new();
+        for item in &mut self.packages {
+            if names.contains(&item.package.name)
+                && !item.is_temporarily_ignored
+                && !item.is_permanently_ignored
+            {
+                item.is_temporarily_ignored = true;
+                item.is_auto_ignored = false;
+                held.push(item.package.name.clone());
+            }
+        }
+
+        self.status_message = Some(if held.is_empty() {
+            "No further packages to hold".to_string()
+        } else {
+            format!("Holding back {} for this run", held.join(", "))
+        });
+        // Clear cache as ignore status affects conflict detection
+        self.reverse_deps_cache.clear();
+    }
+
+    /// Fetches reverse dependencies for every uncached package in one batch
+    ///
+    /// `fetch` receives the uncached names and returns the records it found.
+    /// Found packages are cached; the rest are returned with a per-name error,
+    /// which is the fetch error itself if the whole batch failed.
+    pub fn prefetch_required_by<F>(
+        &mut self,
+        packages: &[String],
+        fetch: F,
+    ) -> HashMap<String, String>
+    where
+        F: FnOnce(&[String]) -> Result<HashMap<String, Vec<String>>, String>,
+    {
+        let uncached: Vec<String> = packages
+            .iter()
+            .filter(|pkg| !self.reverse_deps_cache.contains_key(*pkg))
+            .cloned()
+            .collect();
+        if uncached.is_empty() {
+            return HashMap::new();
+        }
+
+        match fetch(&uncached) {
+            Ok(mut found) => uncached
+                .into_iter()
+                .filter_map(|pkg| {
+                    if let Some(deps) = found.remove(&pkg) {
+                        self.reverse_deps_cache.insert(pkg, deps);
+                        None
+                    } else {
+                        let error = format!("Package '{pkg}' not found");
+                        Some((pkg, error))
+                    }
+                })
+                .collect(),
+            Err(e) => uncached.into_iter().map(|pkg| (pkg, e.clone())).collect(),
+        }
     }
 
     /// Gets or fetches reverse dependencies for a package (with caching)
@@ -269,3 +1338,51 @@ impl AppState {
         }
     }
 }
+
+/// Returns the ignored group or repository covering `package`, groups first.
+fn matching_ignore_set(
+    package: &Package,
+    ignored_groups: &[String],
+    ignored_repos: &[String],
+) -> Option<IgnoreSet> {
+    package
+        .groups
+        .iter()
+        .find(|group| ignored_groups.contains(group))
+        .map(|group| IgnoreSet::Group(group.clone()))
+        .or_else(|| {
+            package
+                .sync_repo
+                .as_ref()
+                .filter(|repo| ignored_repos.contains(repo))
+                .map(|repo| IgnoreSet::Repo(repo.clone()))
+        })
+}
+
+/// Returns the pacman.conf directive that makes pacman skip `package`, if any.
+///
+/// `IgnorePkg` entries use pacman's glob semantics; the app's `re:` prefix
+/// has no meaning there.
+fn pacman_conf_ignore(package: &Package, conf: &PacmanConf) -> Option<String> {
+    conf.ignore_pkg
+        .iter()
+        .find(|rule| filter::glob_match(rule, &package.name))
+        .map(|rule| format!("IgnorePkg {rule}"))
+        .or_else(|| {
+            conf.ignore_group
+                .iter()
+                .find(|group| package.groups.contains(group))
+                .map(|group| format!("IgnoreGroup {group}"))
+        })
+}
+
+/// Returns the repository shown for `package`: its sync repository, else
+/// `AUR_LABEL` or `OFFICIAL_LABEL`.
+#[must_use]
+pub fn repository_label(package: &Package) -> &str {
+    match (&package.sync_repo, package.repository) {
+        (Some(repo), _) => repo,
+        (None, PackageRepository::Aur) => AUR_LABEL,
+        (None, PackageRepository::Official) => OFFICIAL_LABEL,
+    }
+}
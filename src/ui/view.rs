@@ -3,11 +3,17 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
-use super::app::{AppState, LoadingState};
-use crate::models::package::PackageRepository;
+use super::app::{
+    AUR_LABEL, AppState, GraphSection, GraphView, IgnoreSet, LoadingState, OFFICIAL_LABEL,
+    PackageItem, repository_label,
+};
+use crate::core::dependency::{self, DependencyConflict, HoldNode, LibraryBreakage};
+use crate::core::version;
+use crate::models::package::{PackageDetails, UpdateKind};
+use crate::models::transaction::{TransactionAction, TransactionPreview, TransactionTarget};
 
 pub fn render(frame: &mut Frame, state: &AppState) {
     match &state.loading_state {
@@ -20,28 +26,41 @@ pub fn render(frame: &mut Frame, state: &AppState) {
 
 fn render_loading(frame: &mut Frame, state: &AppState) {
     let area = frame.area();
+    let source_lines = u16::try_from(state.scan_progress.len().max(1)).unwrap_or(1);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Percentage(35),
-            Constraint::Length(9),
+            Constraint::Length(8 + source_lines), // 6 fixed lines + sources + 2 borders
             Constraint::Percentage(35),
         ])
         .split(area);
 
+    let spinner_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
     let spinner = get_spinner();
-    let text = vec![
-        Line::from(""),
-        Line::from(vec![
+
+    let mut text = vec![Line::from("")];
+    if state.scan_progress.is_empty() {
+        text.push(Line::from(vec![
+            Span::styled(spinner, spinner_style),
+            Span::raw("  "),
+            Span::styled("Initializing...", Style::default().fg(Color::White)),
+        ]));
+    }
+    for (source, message) in &state.scan_progress {
+        text.push(Line::from(vec![
+            Span::styled(spinner, spinner_style),
+            Span::raw("  "),
             Span::styled(
-                spinner,
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
+                format!("{source}: "),
+                Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("  "),
-            Span::styled(&state.loading_message, Style::default().fg(Color::White)),
-        ]),
+            Span::styled(message.as_str(), Style::default().fg(Color::White)),
+        ]));
+    }
+    text.extend([
         Line::from(""),
         Line::from(Span::styled(
             "Please wait...",
@@ -53,7 +72,7 @@ fn render_loading(frame: &mut Frame, state: &AppState) {
             Style::default().fg(Color::DarkGray),
         )),
         Line::from(""),
-    ];
+    ]);
 
     let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
         Block::default().borders(Borders::ALL).title(Span::styled(
@@ -151,7 +170,21 @@ fn render_main(frame: &mut Frame, state: &AppState) {
         .split(frame.area());
 
     render_header(frame, chunks[0], state);
-    render_package_list(frame, chunks[1], state);
+    if let Some(graph) = &state.graph {
+        render_graph_view(frame, chunks[1], state, graph);
+    } else if state.show_details {
+        let list_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),     // Package list
+                Constraint::Length(14), // Details (2 borders + exclusion + 11 fields)
+            ])
+            .split(chunks[1]);
+        render_package_list(frame, list_chunks[0], state);
+        render_details_pane(frame, list_chunks[1], state);
+    } else {
+        render_package_list(frame, chunks[1], state);
+    }
     render_status(frame, chunks[2], state);
     render_keybinds(frame, chunks[3], state);
 
@@ -162,6 +195,18 @@ fn render_main(frame: &mut Frame, state: &AppState) {
     if state.show_dependency_warning {
         render_dependency_warning_modal(frame, state);
     }
+
+    if state.has_disk_shortages() {
+        render_disk_warning_modal(frame, state);
+    }
+
+    if let Some(preview) = &state.transaction_preview {
+        render_transaction_preview_modal(frame, state, preview);
+    }
+
+    if let Some(input) = &state.note_input {
+        render_note_prompt(frame, state, input);
+    }
 }
 
 fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
@@ -184,6 +229,26 @@ fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
 }
 
 fn render_package_list(frame: &mut Frame, area: Rect, state: &AppState) {
+    // Align the arrows on the longest installed version
+    let version_width = state
+        .packages
+        .iter()
+        .map(|item| {
+            item.package
+                .current_version
+                .as_deref()
+                .map_or(1, |v| v.chars().count())
+        })
+        .max()
+        .unwrap_or(1);
+
+    let badge_width = state
+        .packages
+        .iter()
+        .map(|item| repository_label(&item.package).chars().count())
+        .max()
+        .unwrap_or(0);
+
     let items: Vec<ListItem> = state
         .packages
         .iter()
@@ -201,32 +266,35 @@ fn render_package_list(frame: &mut Frame, area: Rect, state: &AppState) {
                 "  "
             };
 
-            let (badge, badge_color) = match item.package.repository {
-                PackageRepository::Official => ("Official", Color::Blue),
-                PackageRepository::Aur => ("AUR", Color::Yellow),
-            };
+            let badge = repository_label(&item.package);
 
-            let perm_marker = if item.is_permanently_ignored {
-                " (PERM)"
-            } else {
-                ""
-            };
+            let marker = ignore_marker(state, item);
 
-            let version_info = format!(
-                "{:12} -> {}",
-                item.package.current_version.as_deref().unwrap_or("?"),
-                item.package.new_version
-            );
+            let current_version = item.package.current_version.as_deref();
+            let (unchanged_prefix, changed, unchanged_suffix) = current_version
+                .map_or(("", item.package.new_version.as_str(), ""), |current| {
+                    version::split_changed(current, &item.package.new_version)
+                });
+            let dimmed = Style::default().add_modifier(Modifier::DIM);
 
             let line = Line::from(vec![
                 Span::raw(cursor),
                 Span::raw(checkbox),
                 Span::raw(" ["),
-                Span::styled(badge, Style::default().fg(badge_color)),
+                Span::styled(
+                    format!("{badge:^badge_width$}"),
+                    Style::default().fg(repository_color(badge)),
+                ),
                 Span::raw("] "),
                 Span::raw(format!("{:20} ", item.package.name)),
-                Span::raw(version_info),
-                Span::raw(perm_marker),
+                Span::raw(format!(
+                    "{:version_width$} -> ",
+                    current_version.unwrap_or("?")
+                )),
+                Span::styled(unchanged_prefix, dimmed),
+                Span::styled(changed, update_kind_style(item.update_kind)),
+                Span::styled(unchanged_suffix, dimmed),
+                Span::raw(marker),
             ]);
 
             let style = if idx == state.cursor_position {
@@ -265,10 +333,343 @@ fn render_package_list(frame: &mut Frame, area: Rect, state: &AppState) {
     frame.render_widget(list, area);
 }
 
+/// Returns the badge color of a repository label.
+///
+/// Well-known repositories have fixed colors; other repositories get a color
+/// derived from their name, so it stays the same between runs.
+fn repository_color(label: &str) -> Color {
+    const CUSTOM_COLORS: [Color; 5] = [
+        Color::LightGreen,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::LightBlue,
+        Color::LightYellow,
+    ];
+
+    match label {
+        AUR_LABEL => Color::Yellow,
+        OFFICIAL_LABEL | "core" => Color::Blue,
+        "extra" => Color::Cyan,
+        "multilib" => Color::Magenta,
+        _ if label.ends_with("testing") || label.ends_with("staging") => Color::Red,
+        _ => {
+            let hash = label.bytes().fold(0usize, |hash, b| {
+                hash.wrapping_mul(31).wrapping_add(usize::from(b))
+            });
+            CUSTOM_COLORS[hash % CUSTOM_COLORS.len()]
+        },
+    }
+}
+
+/// Explains why a package is ignored, e.g. ` (HOLD: 24.1.0)`; empty if it is not.
+fn ignore_marker(state: &AppState, item: &PackageItem) -> String {
+    if item.pacman_ignore.is_some() {
+        return " (pacman.conf)".to_string();
+    }
+    if let Some(rule) = item.permanent_rule.as_deref() {
+        let note = state
+            .permanent_entry(item)
+            .and_then(|entry| entry.note.as_deref())
+            .map_or_else(String::new, |note| format!(" - {note}"));
+        return if rule == item.package.name {
+            format!(" (PERM){note}")
+        } else {
+            format!(" (PERM: {rule}){note}")
+        };
+    }
+
+    match (&item.hold, &item.snooze, &item.ignore_set) {
+        (Some(hold), _, _) if hold.name != item.package.name => {
+            format!(" (HOLD: {} {})", hold.name, hold.version)
+        },
+        (Some(hold), _, _) => format!(" (HOLD: {})", hold.version),
+        // The date is enough; the time of day is noise in the list
+        (None, Some(snooze), _) => format!(
+            " (SNOOZE until {})",
+            snooze.until.split('T').next().unwrap_or_default()
+        ),
+        (None, None, Some(IgnoreSet::Group(group))) => format!(" (GROUP: {group})"),
+        (None, None, Some(IgnoreSet::Repo(repo))) => format!(" (REPO: {repo})"),
+        (None, None, None) if item.is_auto_ignored => " (AUTO)".to_string(),
+        (None, None, None) => String::new(),
+    }
+}
+
+fn render_details_pane(frame: &mut Frame, area: Rect, state: &AppState) {
+    let title = state
+        .current_package()
+        .map_or_else(String::new, |pkg| format!(" {} ", pkg.name));
+
+    let mut lines = match state.current_details() {
+        Some(Ok(details)) => details_lines(details),
+        Some(Err(e)) => vec![Line::from(Span::styled(
+            e.as_str(),
+            Style::default().fg(Color::Red),
+        ))],
+        None => vec![Line::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::DarkGray),
+        ))],
+    };
+
+    if let Some(entry) = state
+        .packages
+        .get(state.cursor_position)
+        .and_then(|item| state.permanent_entry(item))
+    {
+        let since = entry
+            .added
+            .as_ref()
+            .map_or_else(String::new, |added| format!(" since {added}"));
+        let note = entry
+            .note
+            .as_ref()
+            .map_or_else(String::new, |note| format!(": {note}"));
+        let excluded = format!("by '{}'{since}{note}", entry.name);
+        lines.insert(
+            0,
+            Line::from(vec![
+                Span::styled(
+                    format!("{:15}: ", "Excluded"),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(excluded),
+            ]),
+        );
+    }
+
+    let pane = Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(pane, area);
+}
+
+fn details_lines(details: &PackageDetails) -> Vec<Line<'_>> {
+    let field = |label: &'static str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("{label:15}: "),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(value),
+        ])
+    };
+    let text = |value: Option<&String>| value.map_or_else(|| "-".to_string(), Clone::clone);
+    let list = |values: &[String], separator: &str| {
+        if values.is_empty() {
+            "None".to_string()
+        } else {
+            values.join(separator)
+        }
+    };
+
+    let installed_size = match (details.installed_size, details.current_installed_size) {
+        (Some(new), Some(current)) => {
+            let delta = i64::try_from(new)
+                .ok()
+                .zip(i64::try_from(current).ok())
+                .map_or(0, |(new, current)| new - current);
+            format!("{} ({})", format_size(new), format_size_delta(delta))
+        },
+        (Some(new), None) => format_size(new),
+        (None, _) => "-".to_string(),
+    };
+
+    vec![
+        field("Description", text(details.description.as_ref())),
+        field("Version", text(details.version.as_ref())),
+        field("URL", text(details.url.as_ref())),
+        field("Licenses", list(&details.licenses, " ")),
+        field("Build Date", text(details.build_date.as_ref())),
+        field("Packager", text(details.packager.as_ref())),
+        field(
+            "Download Size",
+            details
+                .download_size
+                .map_or_else(|| "-".to_string(), format_size),
+        ),
+        field("Installed Size", installed_size),
+        field("Depends On", list(&details.depends, "  ")),
+        field("Optional Deps", list(&details.optdepends, ", ")),
+    ]
+}
+
+/// Formats a byte count with binary units, as pacman does.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut unit = 0;
+    let mut scaled = bytes;
+    while scaled >= 1024 && unit < UNITS.len() - 1 {
+        scaled /= 1024;
+        unit += 1;
+    }
+    if unit == 0 {
+        return format!("{bytes} B");
+    }
+    // Two decimals using integer arithmetic to avoid lossy float casts
+    let divisor = 1u64 << (10 * unit);
+    let hundredths = bytes % divisor * 100 / divisor;
+    format!("{}.{hundredths:02} {}", bytes / divisor, UNITS[unit])
+}
+
+/// Formats a signed byte count such as `+340.00 MiB`.
+fn format_size_delta(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_size(bytes.unsigned_abs()))
+}
+
+/// Color-codes a new version by how risky the update is.
+fn update_kind_style(kind: UpdateKind) -> Style {
+    match kind {
+        UpdateKind::Downgrade | UpdateKind::Epoch => Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+        UpdateKind::Major => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        UpdateKind::Minor => Style::default().fg(Color::Yellow),
+        UpdateKind::Patch => Style::default().fg(Color::Green),
+        UpdateKind::PkgrelOnly => Style::default().fg(Color::DarkGray),
+        UpdateKind::Unknown => Style::default(),
+    }
+}
+
+/// Renders the dependency graph browser in place of the package list.
+///
+/// The focused package is the root; its depends, required-by, optdepends and
+/// provides are branches. The selected entry is kept in the middle of the view.
+fn render_graph_view(frame: &mut Frame, area: Rect, state: &AppState, graph: &GraphView) {
+    let trail: Vec<&str> = graph
+        .history
+        .iter()
+        .map(String::as_str)
+        .chain([graph.focus.as_str()])
+        .collect();
+    let block = Block::default()
+        .title(format!("Dependency Graph: {}", trail.join(" › ")))
+        .borders(Borders::ALL);
+
+    let mut lines = vec![Line::from(
+        [
+            vec![Span::styled(
+                graph.focus.as_str(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )],
+            graph_marker(state, &graph.focus),
+        ]
+        .concat(),
+    )];
+    let mut selected_line = 0;
+
+    match state.graph_relations() {
+        None => lines.push(Line::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::DarkGray),
+        ))),
+        Some(Err(e)) => lines.push(Line::from(Span::styled(
+            e.as_str(),
+            Style::default().fg(Color::Red),
+        ))),
+        Some(Ok(relations)) => {
+            let mut index = 0;
+            for (section_idx, section) in GraphSection::ALL.iter().enumerate() {
+                let last_section = section_idx + 1 == GraphSection::ALL.len();
+                let entries = section.entries(relations);
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        if last_section { "└─ " } else { "├─ " },
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        format!("{} ({})", section.label(), entries.len()),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ]));
+
+                let prefix = if last_section { "   " } else { "│  " };
+                for (entry_idx, entry) in entries.iter().enumerate() {
+                    let branch = if entry_idx + 1 == entries.len() {
+                        "└─ "
+                    } else {
+                        "├─ "
+                    };
+                    let mut style = if state.graph_target(*section, entry).is_some() {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    if index == graph.cursor {
+                        style = style.add_modifier(Modifier::REVERSED);
+                        selected_line = lines.len();
+                    }
+                    let mut spans = vec![
+                        Span::styled(
+                            format!("{prefix}{branch}"),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(entry.as_str(), style),
+                    ];
+                    if let Some(target) = state.graph_target(*section, entry) {
+                        // Provisions name the package they resolve to
+                        if section.target(entry) != Some(target) {
+                            spans.push(Span::styled(
+                                format!(" [{target}]"),
+                                Style::default().fg(Color::DarkGray),
+                            ));
+                        }
+                        spans.extend(graph_marker(state, target));
+                    }
+                    lines.push(Line::from(spans));
+                    index += 1;
+                }
+            }
+        },
+    }
+
+    // Keep the selected entry in the middle once the tree outgrows the view
+    let height = usize::from(area.height.saturating_sub(2));
+    let offset = u16::try_from(selected_line.saturating_sub(height / 2)).unwrap_or(u16::MAX);
+    let tree = Paragraph::new(lines).block(block).scroll((offset, 0));
+    frame.render_widget(tree, area);
+}
+
+/// Marks packages in the update set with their new version, or as ignored.
+fn graph_marker<'a>(state: &'a AppState, name: &str) -> Vec<Span<'a>> {
+    match state.find_item(name) {
+        Some(item) if item.is_temporarily_ignored || item.is_permanently_ignored => {
+            vec![Span::styled(" (ignored)", Style::default().fg(Color::Red))]
+        },
+        Some(item) => vec![
+            Span::styled(" → ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                item.package.new_version.as_str(),
+                update_kind_style(item.update_kind),
+            ),
+        ],
+        None => Vec::new(),
+    }
+}
+
 fn render_status(frame: &mut Frame, area: Rect, state: &AppState) {
-    let (official, aur, ignored) = state.stats();
+    let (_, _, ignored) = state.stats();
+    let repos = state
+        .repo_stats()
+        .iter()
+        .map(|(repo, count)| format!("{repo} ({count})"))
+        .collect::<Vec<_>>()
+        .join(" | ");
 
-    let stats_text = format!("Stats: Official ({official}) | AUR ({aur}) | To Ignore: {ignored}");
+    let (download, installed_delta, unknown) = state.size_totals();
+    let unknown_text = if unknown == 0 {
+        String::new()
+    } else {
+        format!(" ({unknown} unsized)")
+    };
+    let stats_text = format!(
+        "Stats: {repos} | To Ignore: {ignored} | Download: {} | Disk: {}{unknown_text}",
+        format_size(download),
+        format_size_delta(installed_delta)
+    );
 
     let status_line = if state.scan_warnings.is_empty() {
         stats_text
@@ -281,12 +682,26 @@ fn render_status(frame: &mut Frame, area: Rect, state: &AppState) {
         }
     };
 
-    let status = Paragraph::new(status_line).block(Block::default().borders(Borders::ALL));
+    let status = if let Some(message) = &state.status_message {
+        Paragraph::new(message.as_str()).style(Style::default().fg(Color::Yellow))
+    } else {
+        Paragraph::new(status_line)
+    }
+    .block(Block::default().borders(Borders::ALL));
     frame.render_widget(status, area);
 }
 
 fn render_keybinds(frame: &mut Frame, area: Rect, state: &AppState) {
-    let base_keybinds = "[Enter] Entire  [o] Official  [Space] Toggle  [p] Perm  [q] Quit";
+    if state.graph.is_some() {
+        let keybinds =
+            Paragraph::new("[j/k] Move  [Enter] Open  [Backspace] Back  [Esc] Close  [q] Quit")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(keybinds, area);
+        return;
+    }
+
+    let base_keybinds = "[Enter] Entire  [o] Official  [Space] Toggle  [p] Perm  [h] Hold  [s] Snooze  [i] Info  [q] Quit";
     let keybinds_text = if state.has_official_scan_failed() {
         format!("{base_keybinds}  [r] Reload")
     } else {
@@ -306,7 +721,7 @@ fn render_help_modal(frame: &mut Frame) {
         Line::from(""),
         Line::from(vec![
             Span::styled("[Enter]   ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(": Update Entire System (paru)"),
+            Span::raw(": Update Entire System (AUR helper)"),
         ]),
         Line::from(vec![
             Span::styled("[o]       ", Style::default().add_modifier(Modifier::BOLD)),
@@ -320,6 +735,30 @@ fn render_help_modal(frame: &mut Frame) {
             Span::styled("[p]       ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(": Toggle Permanent Ignore"),
         ]),
+        Line::from(vec![
+            Span::styled("[h]       ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(": Hold / Release Pending Version"),
+        ]),
+        Line::from(vec![
+            Span::styled("[s]       ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(": Snooze / Wake Package"),
+        ]),
+        Line::from(vec![
+            Span::styled("[g]       ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(": Ignore Package's Group, Next Group (this run)"),
+        ]),
+        Line::from(vec![
+            Span::styled("[R]       ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(": Ignore Package's Repository (this run)"),
+        ]),
+        Line::from(vec![
+            Span::styled("[i]       ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(": Toggle Package Info Pane"),
+        ]),
+        Line::from(vec![
+            Span::styled("[d]       ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(": Browse Dependency Graph"),
+        ]),
         Line::from(vec![
             Span::styled("[j/k]     ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(": Navigate List"),
@@ -337,6 +776,19 @@ fn render_help_modal(frame: &mut Frame) {
             Span::raw(": Reload Scan (when failed)"),
         ]),
         Line::from(""),
+        Line::from(vec![
+            Span::raw(" Versions: "),
+            Span::styled("epoch/downgrade", update_kind_style(UpdateKind::Epoch)),
+            Span::raw(" "),
+            Span::styled("major", update_kind_style(UpdateKind::Major)),
+            Span::raw(" "),
+            Span::styled("minor", update_kind_style(UpdateKind::Minor)),
+            Span::raw(" "),
+            Span::styled("patch", update_kind_style(UpdateKind::Patch)),
+            Span::raw(" "),
+            Span::styled("pkgrel", update_kind_style(UpdateKind::PkgrelOnly)),
+        ]),
+        Line::from(""),
         Line::from(vec![
             Span::raw(" GitHub: "),
             Span::styled(
@@ -368,15 +820,26 @@ fn render_help_modal(frame: &mut Frame) {
 
 fn render_dependency_warning_modal(frame: &mut Frame, state: &AppState) {
     // Calculate required height based on content
-    let header_lines = 5; // Empty + title + empty + description + empty
-    let footer_lines = 4; // Empty + warning + empty + keybinds
+    let header_lines = 3; // Empty + title + empty
+    let footer_lines = 5; // Empty + warning + empty + fixes + keybinds
 
     let mut content_lines = 0;
+    if !state.dependency_conflicts.is_empty() {
+        content_lines += 2; // Description + empty
+    }
     for conflict in &state.dependency_conflicts {
         content_lines += 1; // Package name line ("• pkg is required by:")
-        content_lines += conflict.required_by.len(); // Each "→ dep" line
+        content_lines += conflict.violations.len() + conflict.required_by.len(); // Each "→ dep" line
         content_lines += 1; // Blank line separator
     }
+    if !state.library_breakages.is_empty() {
+        content_lines += 3 + state.library_breakages.len(); // Description + empty + lines + empty
+    }
+    if !state.hold_trees.is_empty() {
+        // Description + empty + roots + dependents + empty
+        content_lines +=
+            3 + state.hold_trees.len() + dependency::held_dependents(&state.hold_trees).len();
+    }
 
     let total_lines = header_lines + content_lines + footer_lines;
 
@@ -405,43 +868,256 @@ fn render_dependency_warning_modal(frame: &mut Frame, state: &AppState) {
             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
+    ];
+
+    if !state.dependency_conflicts.is_empty() {
+        warning_lines.extend([
+            Line::from(Span::styled(
+                "The following ignored packages are required by packages being updated:",
+                Style::default().fg(Color::Yellow),
+            )),
+            Line::from(""),
+        ]);
+    }
+    for conflict in &state.dependency_conflicts {
+        warning_lines.extend(conflict_lines(conflict));
+    }
+    warning_lines.extend(breakage_lines(&state.library_breakages));
+    warning_lines.extend(hold_tree_lines(&state.hold_trees));
+
+    let mut fix_spans = vec![
+        Span::styled("[i] ", Style::default().fg(Color::Yellow)),
+        Span::raw("Ignore dependents too  "),
+        Span::styled("[u] ", Style::default().fg(Color::Yellow)),
+        Span::raw("Un-ignore required packages"),
+    ];
+    if !state.hold_trees.is_empty() {
+        fix_spans.extend([
+            Span::styled("  [H] ", Style::default().fg(Color::Yellow)),
+            Span::raw("Hold all of these for this run"),
+        ]);
+    }
+
+    warning_lines.extend([
+        Line::from(""),
         Line::from(Span::styled(
-            "The following ignored packages are required by packages being updated:",
-            Style::default().fg(Color::Yellow),
+            "Proceeding may cause a partial upgrade and break your system.",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
+        Line::from(fix_spans),
+        Line::from(vec![
+            Span::styled("[y] ", Style::default().fg(Color::Green)),
+            Span::raw("Proceed anyway  "),
+            Span::styled("[n] ", Style::default().fg(Color::Red)),
+            Span::raw("Cancel  "),
+            Span::styled("[Esc] ", Style::default().fg(Color::DarkGray)),
+            Span::raw("Cancel"),
+        ]),
+    ]);
+
+    // Use Paragraph with wrap to handle long lines gracefully
+    // LIMITATION: Dynamic height calculation assumes no text wrapping.
+    // If package names exceed modal width, wrapped lines will increase
+    // actual height beyond calculated value, potentially cutting off content.
+    // For production use, consider implementing proper scrolling or using
+    // fixed maximum height with scroll state.
+    let warning = Paragraph::new(warning_lines)
+        .block(
+            Block::default()
+                .title("⚠ WARNING ⚠")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .alignment(Alignment::Left)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(warning, area);
+}
+
+/// Lines describing one ignored package and the updates that need a newer version of it.
+fn conflict_lines(conflict: &DependencyConflict) -> Vec<Line<'_>> {
+    let mut header = vec![
+        Span::styled("  • ", Style::default().fg(Color::Red)),
+        Span::styled(
+            &conflict.ignored_package,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
     ];
+    if let Some(installed) = &conflict.installed_version {
+        header.push(Span::styled(
+            format!(" {installed}"),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    header.push(Span::raw(" is required by:"));
 
-    for conflict in &state.dependency_conflicts {
-        warning_lines.push(Line::from(vec![
+    let mut lines = vec![Line::from(header)];
+    for violation in &conflict.violations {
+        lines.push(Line::from(vec![
+            Span::raw("      → "),
+            Span::styled(&violation.package, Style::default().fg(Color::White)),
+            Span::raw(format!(" {} needs ", violation.new_version)),
+            Span::styled(&violation.constraint, Style::default().fg(Color::Red)),
+        ]));
+    }
+    for dep in &conflict.required_by {
+        lines.push(Line::from(vec![
+            Span::raw("      → "),
+            Span::styled(dep, Style::default().fg(Color::White)),
+            Span::styled(
+                " (new dependencies unknown)",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines
+}
+
+/// Lines listing packages that stay behind but lose a library they need.
+fn breakage_lines(breakages: &[LibraryBreakage]) -> Vec<Line<'_>> {
+    if breakages.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "The following packages keep their version but will lose a library they need:",
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+    ];
+    for breakage in breakages {
+        lines.push(Line::from(vec![
             Span::styled("  • ", Style::default().fg(Color::Red)),
             Span::styled(
-                &conflict.ignored_package,
+                &breakage.package,
                 Style::default()
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" is required by:"),
+            Span::raw(" needs "),
+            Span::styled(&breakage.dependency, Style::default().fg(Color::Red)),
+            Span::styled(
+                format!(" (no longer provided by updated {})", breakage.provider),
+                Style::default().fg(Color::DarkGray),
+            ),
         ]));
+    }
+    lines.push(Line::from(""));
+    lines
+}
 
-        for dep in &conflict.required_by {
-            warning_lines.push(Line::from(vec![
-                Span::raw("      → "),
-                Span::styled(dep, Style::default().fg(Color::White)),
+/// Lines showing everything that must be held back, as one tree per ignored package.
+fn hold_tree_lines(trees: &[HoldNode]) -> Vec<Line<'_>> {
+    fn push_dependents<'a>(node: &'a HoldNode, prefix: &str, lines: &mut Vec<Line<'a>>) {
+        for (index, dependent) in node.dependents.iter().enumerate() {
+            let last = index + 1 == node.dependents.len();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{prefix}{}", if last { "└─ " } else { "├─ " }),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(&dependent.package, Style::default().fg(Color::White)),
             ]));
+            let child_prefix = format!("{prefix}{}", if last { "   " } else { "│  " });
+            push_dependents(dependent, &child_prefix, lines);
         }
-        warning_lines.push(Line::from(""));
+    }
+
+    if trees.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "To stay consistent, these must be held back as well:",
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+    ];
+    for tree in trees {
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                &tree.package,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        push_dependents(tree, "  ", &mut lines);
+    }
+    lines.push(Line::from(""));
+    lines
+}
+
+fn render_disk_warning_modal(frame: &mut Frame, state: &AppState) {
+    let area = centered_rect(70, 50, frame.area());
+
+    let mut warning_lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "⚠ NOT ENOUGH DISK SPACE ⚠",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "The following filesystems are too full for this update:",
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+    ];
+
+    for shortage in &state.disk_shortages {
+        warning_lines.push(Line::from(vec![
+            Span::styled("  • ", Style::default().fg(Color::Red)),
+            Span::styled(
+                &shortage.mount_point,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" ({})", shortage.purpose)),
+        ]));
+        warning_lines.push(Line::from(format!(
+            "      needs {}, {} free",
+            format_size(shortage.required),
+            format_size(shortage.available)
+        )));
     }
 
     warning_lines.extend([
         Line::from(""),
         Line::from(Span::styled(
-            "Proceeding may cause a partial upgrade and break your system.",
+            "Packages already in the package cache are not counted as downloads.",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]);
+    let (_, _, unknown) = state.size_totals();
+    if unknown > 0 {
+        warning_lines.push(Line::from(Span::styled(
+            format!("{unknown} package(s) with unknown sizes (e.g. AUR builds) are not counted."),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    warning_lines.extend([
+        Line::from(""),
+        Line::from(Span::styled(
+            "Running out of space mid-update can leave the system unbootable.",
             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("[y] ", Style::default().fg(Color::Green)),
+            Span::styled("[c] ", Style::default().fg(Color::Green)),
+            Span::raw("Clean package cache  "),
+            Span::styled("[y] ", Style::default().fg(Color::Yellow)),
             Span::raw("Proceed anyway  "),
             Span::styled("[n] ", Style::default().fg(Color::Red)),
             Span::raw("Cancel  "),
@@ -450,12 +1126,6 @@ fn render_dependency_warning_modal(frame: &mut Frame, state: &AppState) {
         ]),
     ]);
 
-    // Use Paragraph with wrap to handle long lines gracefully
-    // LIMITATION: Dynamic height calculation assumes no text wrapping.
-    // If package names exceed modal width, wrapped lines will increase
-    // actual height beyond calculated value, potentially cutting off content.
-    // For production use, consider implementing proper scrolling or using
-    // fixed maximum height with scroll state.
     let warning = Paragraph::new(warning_lines)
         .block(
             Block::default()
@@ -465,12 +1135,223 @@ fn render_dependency_warning_modal(frame: &mut Frame, state: &AppState) {
                 .style(Style::default().bg(Color::Black)),
         )
         .alignment(Alignment::Left)
-        .wrap(ratatui::widgets::Wrap { trim: false });
+        .wrap(Wrap { trim: false });
 
     frame.render_widget(Clear, area);
     frame.render_widget(warning, area);
 }
 
+fn render_transaction_preview_modal(
+    frame: &mut Frame,
+    state: &AppState,
+    preview: &Result<TransactionPreview, String>,
+) {
+    let area = centered_rect(80, 80, frame.area());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "TRANSACTION PREVIEW",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    match preview {
+        Ok(preview) => lines.extend(transaction_lines(preview)),
+        Err(error) => {
+            lines.push(Line::from(Span::styled(
+                "pacman could not prepare the transaction:",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            lines.extend(error.lines().map(|line| Line::from(format!("  {line}"))));
+        },
+    }
+
+    let block = Block::default()
+        .title("Simulated Update (pacman --print)")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(2)])
+        .split(inner);
+
+    let visible = usize::from(chunks[0].height);
+    let scroll = state
+        .transaction_scroll
+        .min(lines.len().saturating_sub(visible));
+    let body = Paragraph::new(lines)
+        .scroll((u16::try_from(scroll).unwrap_or(u16::MAX), 0))
+        .wrap(Wrap { trim: false });
+    let footer = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[j/k] ", Style::default().fg(Color::Cyan)),
+            Span::raw("Scroll  "),
+            Span::styled("[y/Enter] ", Style::default().fg(Color::Green)),
+            Span::raw("Proceed  "),
+            Span::styled("[n/Esc] ", Style::default().fg(Color::Red)),
+            Span::raw("Cancel  "),
+            Span::styled("[q] ", Style::default().fg(Color::DarkGray)),
+            Span::raw("Quit"),
+        ]),
+    ]);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    frame.render_widget(body, chunks[0]);
+    frame.render_widget(footer, chunks[1]);
+}
+
+/// Lists the simulated transaction, surprises first: replacements, removals,
+/// new dependencies and skipped updates, then the plain upgrades.
+fn transaction_lines(preview: &TransactionPreview) -> Vec<Line<'static>> {
+    let mut replacements = Vec::new();
+    let mut installs = Vec::new();
+    let mut upgrades = Vec::new();
+    for entry in &preview.entries {
+        let target = &entry.target;
+        match &entry.action {
+            TransactionAction::Replace { replaces } => replacements.push(Line::from(vec![
+                Span::styled("  + ", Style::default().fg(Color::Magenta)),
+                Span::raw(target_label(target)),
+                Span::styled(
+                    format!(" replaces {}", replaces.join(", ")),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ])),
+            TransactionAction::Install => installs.push(Line::from(vec![
+                Span::styled("  + ", Style::default().fg(Color::Yellow)),
+                Span::raw(target_label(target)),
+            ])),
+            TransactionAction::Upgrade { from } => upgrades.push(Line::from(vec![
+                Span::styled("  ↑ ", Style::default().fg(Color::Green)),
+                Span::raw(format!(
+                    "{} {from} → {} {}",
+                    target.name,
+                    target.version,
+                    target_source(target)
+                )),
+            ])),
+        }
+    }
+    let download: u64 = preview
+        .entries
+        .iter()
+        .map(|entry| entry.target.download_size)
+        .sum();
+
+    let mut lines = vec![
+        Line::from(format!(
+            "{} upgraded, {} new, {} replacing, {} removed; {} to download",
+            upgrades.len(),
+            installs.len(),
+            replacements.len(),
+            preview.removals.len(),
+            format_size(download)
+        )),
+        Line::from(""),
+    ];
+    if preview.entries.is_empty() {
+        lines.push(Line::from("pacman has nothing to upgrade."));
+    }
+
+    let removals = preview.removals.iter().map(|name| {
+        Line::from(vec![
+            Span::styled("  - ", Style::default().fg(Color::Red)),
+            Span::raw(name.clone()),
+        ])
+    });
+    let skipped = preview.skipped.iter().map(|name| {
+        Line::from(vec![
+            Span::styled("  = ", Style::default().fg(Color::DarkGray)),
+            Span::raw(name.clone()),
+        ])
+    });
+    let sections = [
+        ("Replacements:", Color::Magenta, replacements),
+        ("Removed:", Color::Red, removals.collect()),
+        ("New dependencies:", Color::Yellow, installs),
+        (
+            "Listed but not upgraded:",
+            Color::DarkGray,
+            skipped.collect(),
+        ),
+        ("Upgrades:", Color::Green, upgrades),
+    ];
+    for (title, color, section) in sections {
+        if section.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(Span::styled(
+            title,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )));
+        lines.extend(section);
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+/// Formats a transaction target as `name version (repository, size)`.
+fn target_label(target: &TransactionTarget) -> String {
+    format!(
+        "{} {} {}",
+        target.name,
+        target.version,
+        target_source(target)
+    )
+}
+
+/// Formats where a transaction target comes from as `(repository, size)`.
+fn target_source(target: &TransactionTarget) -> String {
+    format!(
+        "({}, {})",
+        target.repository,
+        format_size(target.download_size)
+    )
+}
+
+fn render_note_prompt(frame: &mut Frame, state: &AppState, input: &str) {
+    let area = centered_rect(60, 30, frame.area());
+    let name = state.current_package().map_or("", |pkg| pkg.name.as_str());
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(format!("Why is {name} excluded? (optional)")),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(input),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[Enter] ", Style::default().fg(Color::Green)),
+            Span::raw("Exclude  "),
+            Span::styled("[Esc] ", Style::default().fg(Color::DarkGray)),
+            Span::raw("Cancel"),
+        ]),
+    ];
+
+    let prompt = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Permanent Exclusion")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(prompt, area);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -0,0 +1,158 @@
+use par_tui::io::command::{CommandError, run_with_timeout};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Returns a per-test file the child writes its background `sleep` PID to.
+fn pid_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("par_tui_{name}_{}.pid", std::process::id()))
+}
+
+/// Starts a shell that leaves a `sleep` running in its process group,
+/// the way `checkupdates` leaves `pacman` running.
+fn sleeping_group(pid_file: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command.args([
+        "-c",
+        &format!("sleep 30 & echo $! > {}; wait", pid_file.display()),
+    ]);
+    command
+}
+
+/// Starts a shell that exits at once, leaving a `sleep` that holds its
+/// output pipes open, the way a helper can leave a daemonized agent behind.
+fn lingering_pipe_holder(pid_file: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command.args([
+        "-c",
+        &format!("sleep 30 & echo $! > {}", pid_file.display()),
+    ]);
+    command
+}
+
+/// Reads the PID written by `sleeping_group`, waiting for the shell to write it.
+fn read_pid(pid_file: &Path) -> u32 {
+    for _ in 0..50 {
+        if let Some(pid) = std::fs::read_to_string(pid_file)
+            .ok()
+            .and_then(|content| content.trim().parse().ok())
+        {
+            return pid;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("child did not write its pid");
+}
+
+/// A killed process is gone, or a zombie until its new parent reaps it.
+fn is_running(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| {
+        !stat
+            .rsplit(')')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .starts_with('Z')
+    })
+}
+
+/// Waits briefly for `pid` to exit after the group was signalled.
+fn exits_soon(pid: u32) -> bool {
+    (0..50).any(|_| {
+        let running = is_running(pid);
+        if running {
+            thread::sleep(Duration::from_millis(20));
+        }
+        !running
+    })
+}
+
+#[test]
+fn test_run_with_timeout_collects_output() {
+    let Ok(output) = run_with_timeout(
+        Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+        Duration::from_secs(5),
+        &AtomicBool::new(false),
+    ) else {
+        panic!("short command should finish");
+    };
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+}
+
+#[test]
+fn test_run_with_timeout_kills_group_on_timeout() {
+    let pid_file = pid_file("timeout");
+    let started = Instant::now();
+
+    let result = run_with_timeout(
+        &mut sleeping_group(&pid_file),
+        Duration::from_millis(500),
+        &AtomicBool::new(false),
+    );
+
+    assert!(matches!(result, Err(CommandError::TimedOut(_))));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    let pid = read_pid(&pid_file);
+    let _ = std::fs::remove_file(&pid_file);
+    assert!(
+        exits_soon(pid),
+        "background sleep {pid} survived the timeout"
+    );
+}
+
+#[test]
+fn test_run_with_timeout_kills_group_on_cancel() {
+    let pid_file = pid_file("cancel");
+    let cancel = Arc::new(AtomicBool::new(false));
+    let canceller = {
+        let cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            cancel.store(true, Ordering::Relaxed);
+        })
+    };
+    let started = Instant::now();
+
+    let result = run_with_timeout(
+        &mut sleeping_group(&pid_file),
+        Duration::from_secs(30),
+        &cancel,
+    );
+    let _ = canceller.join();
+
+    assert!(matches!(result, Err(CommandError::Cancelled)));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    let pid = read_pid(&pid_file);
+    let _ = std::fs::remove_file(&pid_file);
+    assert!(
+        exits_soon(pid),
+        "background sleep {pid} survived cancellation"
+    );
+}
+
+#[test]
+fn test_run_with_timeout_bounds_pipes_held_after_exit() {
+    let pid_file = pid_file("pipes");
+    let started = Instant::now();
+
+    let result = run_with_timeout(
+        &mut lingering_pipe_holder(&pid_file),
+        Duration::from_millis(500),
+        &AtomicBool::new(false),
+    );
+
+    assert!(matches!(result, Err(CommandError::TimedOut(_))));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    let pid = read_pid(&pid_file);
+    let _ = std::fs::remove_file(&pid_file);
+    assert!(
+        exits_soon(pid),
+        "background sleep {pid} kept the pipes open"
+    );
+}
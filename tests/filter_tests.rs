@@ -1,6 +1,9 @@
-use par_tui::core::filter::{apply_permanent_excludes, apply_temporary_excludes};
-use par_tui::models::config::Config;
-use par_tui::models::package::{Package, PackageRepository};
+use par_tui::core::filter::{
+    NamePattern, PatternCache, apply_permanent_excludes, apply_temporary_excludes, glob_match,
+    invalid_patterns, matches_auto_ignore, matching_hold, matching_rule, rule_matches,
+};
+use par_tui::models::config::{AutoIgnoreRule, Config, HoldRule};
+use par_tui::models::package::{Package, PackageRepository, UpdateKind};
 
 fn make_test_package(name: &str) -> Package {
     Package {
@@ -8,6 +11,11 @@ fn make_test_package(name: &str) -> Package {
         current_version: Some("1.0.0".to_string()),
         new_version: "2.0.0".to_string(),
         repository: PackageRepository::Official,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
     }
 }
 
@@ -30,7 +38,7 @@ fn test_apply_permanent_excludes_some() {
     ];
 
     let mut config = Config::default();
-    config.exclude.permanent = vec!["pkg2".to_string()];
+    config.exclude.permanent = vec!["pkg2".into()];
     let filtered = apply_permanent_excludes(packages, &config);
 
     assert_eq!(filtered.len(), 2);
@@ -42,7 +50,7 @@ fn test_apply_permanent_excludes_all() {
     let packages = vec![make_test_package("pkg1"), make_test_package("pkg2")];
 
     let mut config = Config::default();
-    config.exclude.permanent = vec!["pkg1".to_string(), "pkg2".to_string()];
+    config.exclude.permanent = vec!["pkg1".into(), "pkg2".into()];
     let filtered = apply_permanent_excludes(packages, &config);
 
     assert_eq!(filtered.len(), 0);
@@ -71,3 +79,179 @@ fn test_apply_temporary_excludes_empty() {
 
     assert_eq!(filtered.len(), 2);
 }
+
+#[test]
+fn test_matches_auto_ignore_with_repository() {
+    let rules = vec![AutoIgnoreRule {
+        kind: UpdateKind::PkgrelOnly,
+        repository: Some(PackageRepository::Aur),
+    }];
+
+    assert!(matches_auto_ignore(
+        UpdateKind::PkgrelOnly,
+        PackageRepository::Aur,
+        &rules
+    ));
+    assert!(!matches_auto_ignore(
+        UpdateKind::PkgrelOnly,
+        PackageRepository::Official,
+        &rules
+    ));
+    assert!(!matches_auto_ignore(
+        UpdateKind::Patch,
+        PackageRepository::Aur,
+        &rules
+    ));
+}
+
+#[test]
+fn test_matches_auto_ignore_any_repository() {
+    let rules = vec![AutoIgnoreRule {
+        kind: UpdateKind::PkgrelOnly,
+        repository: None,
+    }];
+
+    assert!(matches_auto_ignore(
+        UpdateKind::PkgrelOnly,
+        PackageRepository::Official,
+        &rules
+    ));
+    assert!(!matches_auto_ignore(
+        UpdateKind::Major,
+        PackageRepository::Aur,
+        &[]
+    ));
+}
+
+#[test]
+fn test_rule_matches_glob() {
+    assert!(rule_matches("*-git", "hobby-app-git"));
+    assert!(!rule_matches("*-git", "git"));
+    assert!(rule_matches("nvidia-*", "nvidia-utils"));
+    assert!(!rule_matches("nvidia-*", "lib32-nvidia-utils"));
+    assert!(rule_matches("python-?ip", "python-pip"));
+    assert!(rule_matches("*lib*-bin", "foo-libbar-bin"));
+    assert!(rule_matches("*", "anything"));
+}
+
+#[test]
+fn test_rule_matches_regex_and_literal() {
+    assert!(rule_matches("re:^python-.*-bin$", "python-foo-bin"));
+    assert!(!rule_matches("re:^python-.*-bin$", "python-foo"));
+    // Unanchored regexes match anywhere in the name
+    assert!(rule_matches("re:nvidia", "lib32-nvidia-utils"));
+    assert!(!rule_matches("re:(", "("));
+    assert!(rule_matches("linux", "linux"));
+    assert!(!rule_matches("linux", "linux-lts"));
+}
+
+#[test]
+fn test_compiled_patterns_match_like_rules() {
+    let rules = ["*-git", "re:^python-.*-bin$", "re:(", "linux"];
+    let names = [
+        "foo-git",
+        "python-foo-bin",
+        "python-foo",
+        "(",
+        "linux",
+        "linux-lts",
+    ];
+    let mut patterns = PatternCache::default();
+    patterns.compile(rules);
+
+    for rule in rules {
+        let compiled = NamePattern::new(rule);
+        for name in names {
+            assert_eq!(
+                compiled.matches(name),
+                rule_matches(rule, name),
+                "{rule} {name}"
+            );
+            assert_eq!(
+                patterns.matches(rule, name),
+                rule_matches(rule, name),
+                "{rule} {name}"
+            );
+        }
+    }
+    // Rules added after loading still match, compiled on the fly
+    assert!(patterns.matches("nvidia-*", "nvidia-utils"));
+}
+
+#[test]
+fn test_matching_rule_prefers_literal() {
+    let rules = vec!["*-git".to_string(), "foo-git".to_string()];
+    let patterns = PatternCache::default();
+
+    assert_eq!(matching_rule("foo-git", &rules, &patterns), Some("foo-git"));
+    assert_eq!(matching_rule("bar-git", &rules, &patterns), Some("*-git"));
+    assert_eq!(matching_rule("bar", &rules, &patterns), None);
+}
+
+#[test]
+fn test_apply_permanent_excludes_pattern() {
+    let packages = vec![
+        make_test_package("foo-git"),
+        make_test_package("nvidia-utils"),
+        make_test_package("mesa"),
+    ];
+
+    let mut config = Config::default();
+    config.exclude.permanent = vec!["*-git".into(), "re:^nvidia-".into()];
+    let filtered = apply_permanent_excludes(packages, &config);
+
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].name, "mesa");
+}
+
+#[test]
+fn test_invalid_patterns() {
+    let rules = vec![
+        "re:^ok$".to_string(),
+        "re:(unclosed".to_string(),
+        "*-git".to_string(),
+    ];
+    let invalid = invalid_patterns(&rules);
+
+    assert_eq!(invalid.len(), 1);
+    assert_eq!(invalid[0].0, "re:(unclosed");
+}
+
+#[test]
+fn test_matching_hold() {
+    let holds = vec![
+        HoldRule {
+            name: "mesa".to_string(),
+            version: "24.1.0".to_string(),
+        },
+        HoldRule {
+            name: "linux-lts*".to_string(),
+            version: ">=6.7".to_string(),
+        },
+    ];
+    let mut patterns = PatternCache::default();
+    patterns.compile(holds.iter().map(|hold| hold.name.as_str()));
+
+    let mut mesa = make_test_package("mesa");
+    mesa.new_version = "24.1.0-2".to_string();
+    assert_eq!(matching_hold(&mesa, &holds, &patterns), Some(&holds[0]));
+
+    // Upstream moved past the held version
+    mesa.new_version = "24.1.1-1".to_string();
+    assert_eq!(matching_hold(&mesa, &holds, &patterns), None);
+
+    let mut lts = make_test_package("linux-lts-headers");
+    lts.new_version = "6.12.1-1".to_string();
+    assert_eq!(matching_hold(&lts, &holds, &patterns), Some(&holds[1]));
+    lts.new_version = "6.6.60-1".to_string();
+    assert_eq!(matching_hold(&lts, &holds, &patterns), None);
+}
+
+#[test]
+fn test_glob_match_has_no_regex_rules() {
+    assert!(glob_match("*.conf", "mirrorlist.conf"));
+    assert!(glob_match("linux-lts", "linux-lts"));
+    assert!(!glob_match("linux", "linux-lts"));
+    assert!(!glob_match("re:^linux", "linux"));
+    assert!(glob_match("re:^linux", "re:^linux"));
+}
@@ -1,5 +1,5 @@
 use par_tui::core::planner::{UpdateMode, create_plan};
-use par_tui::models::config::Config;
+use par_tui::models::config::{AurHelper, Config};
 use par_tui::models::package::{Package, PackageRepository};
 
 fn make_test_package(name: &str, repo: PackageRepository) -> Package {
@@ -8,6 +8,11 @@ fn make_test_package(name: &str, repo: PackageRepository) -> Package {
         current_version: Some("1.0.0".to_string()),
         new_version: "2.0.0".to_string(),
         repository: repo,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
     }
 }
 
@@ -94,3 +99,94 @@ fn test_extra_args_in_command() {
 
     assert_eq!(cmd.join(" "), "paru -Syu --noconfirm --needed");
 }
+
+#[test]
+fn test_entire_system_with_yay_helper() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Aur)];
+
+    let plan = create_plan(UpdateMode::EntireSystem, packages, vec!["pkg1".to_string()]);
+    let mut config = Config::default();
+    config.helper.name = Some(AurHelper::Yay);
+    let cmd = plan.build_command(&config);
+
+    assert_eq!(cmd.join(" "), "yay -Syu --ignore pkg1");
+}
+
+#[test]
+fn test_entire_system_with_aurutils_helper() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Aur)];
+
+    let plan = create_plan(UpdateMode::EntireSystem, packages, vec!["pkg1".to_string()]);
+    let mut config = Config::default();
+    config.helper.name = Some(AurHelper::Aurutils);
+    config.behavior.extra_args = vec!["--noconfirm".to_string()];
+    let cmd = plan.build_command(&config);
+
+    assert_eq!(cmd[0], "sh");
+    assert_eq!(cmd[1], "-c");
+    assert_eq!(
+        cmd[2],
+        "aur sync -u --ignore pkg1 && sudo pacman -Syu --ignore pkg1 --noconfirm"
+    );
+}
+
+#[test]
+fn test_ignore_groups() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+
+    let mut plan = create_plan(UpdateMode::EntireSystem, packages, vec!["pkg1".to_string()]);
+    plan.ignore_groups = vec!["gnome".to_string(), "kde-applications".to_string()];
+    let cmd = plan.build_command(&Config::default());
+
+    assert_eq!(
+        cmd.join(" "),
+        "paru -Syu --ignore pkg1 --ignoregroup gnome,kde-applications"
+    );
+}
+
+#[test]
+fn test_ignore_groups_skipped_for_aur_sync() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Aur)];
+
+    let mut plan = create_plan(UpdateMode::EntireSystem, packages, vec![]);
+    plan.ignore_groups = vec!["gnome".to_string()];
+    let mut config = Config::default();
+    config.helper.name = Some(AurHelper::Aurutils);
+    let cmd = plan.build_command(&config);
+
+    assert_eq!(
+        cmd[2],
+        "aur sync -u && sudo pacman -Syu --ignoregroup gnome"
+    );
+}
+
+#[test]
+fn test_ignore_groups_members_skipped_by_aur_sync() {
+    let mut themed = make_test_package("gnome-shell-theme-git", PackageRepository::Aur);
+    themed.groups = vec!["gnome".to_string()];
+    let packages = vec![themed, make_test_package("pkg1", PackageRepository::Aur)];
+
+    let mut plan = create_plan(UpdateMode::EntireSystem, packages, vec!["pkg1".to_string()]);
+    plan.ignore_groups = vec!["gnome".to_string()];
+    let mut config = Config::default();
+    config.helper.name = Some(AurHelper::Aurutils);
+    let cmd = plan.build_command(&config);
+
+    assert_eq!(
+        cmd[2],
+        "aur sync -u --ignore pkg1,gnome-shell-theme-git \
+         && sudo pacman -Syu --ignore pkg1 --ignoregroup gnome"
+    );
+}
+
+#[test]
+fn test_official_only_ignores_helper() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+
+    let plan = create_plan(UpdateMode::OfficialOnly, packages, vec![]);
+    let mut config = Config::default();
+    config.helper.name = Some(AurHelper::Pikaur);
+    let cmd = plan.build_command(&config);
+
+    assert_eq!(cmd.join(" "), "sudo pacman -Syu");
+}
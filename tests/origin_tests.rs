@@ -0,0 +1,114 @@
+use par_tui::core::origin::annotate_origins;
+use par_tui::models::package::{DbPackage, Package, PackageRepository};
+
+fn make_test_package(name: &str, new_version: &str, repository: PackageRepository) -> Package {
+    Package {
+        name: name.to_string(),
+        current_version: Some("1.0-1".to_string()),
+        new_version: new_version.to_string(),
+        repository,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
+    }
+}
+
+fn make_db_package(name: &str, version: &str, repo: &str, groups: &[&str]) -> DbPackage {
+    DbPackage {
+        name: name.to_string(),
+        version: version.to_string(),
+        groups: groups.iter().map(ToString::to_string).collect(),
+        repository: Some(repo.to_string()),
+        ..DbPackage::default()
+    }
+}
+
+#[test]
+fn test_annotate_origins() {
+    let mut packages = vec![
+        make_test_package("nautilus", "47.1-1", PackageRepository::Official),
+        make_test_package("lib32-mesa", "24.2.1-1", PackageRepository::Official),
+        make_test_package("yay-bin", "12.4.2-1", PackageRepository::Aur),
+    ];
+    let sync = vec![
+        make_db_package("nautilus", "47.1-1", "extra", &["gnome"]),
+        make_db_package("lib32-mesa", "24.2.1-1", "multilib", &[]),
+    ];
+
+    annotate_origins(&mut packages, &sync);
+
+    assert_eq!(packages[0].sync_repo.as_deref(), Some("extra"));
+    assert_eq!(packages[0].groups, vec!["gnome"]);
+    assert_eq!(packages[1].sync_repo.as_deref(), Some("multilib"));
+    assert!(packages[1].groups.is_empty());
+    assert_eq!(packages[2].sync_repo, None);
+}
+
+#[test]
+fn test_annotate_origins_prefers_matching_version() {
+    let mut packages = vec![make_test_package(
+        "mesa",
+        "24.2.1-1",
+        PackageRepository::Official,
+    )];
+    let sync = vec![
+        make_db_package("mesa", "24.3.0-1", "testing", &[]),
+        make_db_package("mesa", "24.2.1-1", "extra", &[]),
+    ];
+
+    annotate_origins(&mut packages, &sync);
+
+    assert_eq!(packages[0].sync_repo.as_deref(), Some("extra"));
+}
+
+#[test]
+fn test_annotate_origins_reads_new_depends() {
+    let mut packages = vec![
+        make_test_package("mesa", "24.2.1-1", PackageRepository::Official),
+        make_test_package("yay-bin", "12.4.2-1", PackageRepository::Aur),
+    ];
+    let mut record = make_db_package("mesa", "24.2.1-1", "extra", &[]);
+    record.depends = vec!["libdrm>=2.4.121".to_string(), "expat".to_string()];
+
+    annotate_origins(&mut packages, &[record]);
+
+    assert_eq!(
+        packages[0].depends,
+        Some(vec!["libdrm>=2.4.121".to_string(), "expat".to_string()])
+    );
+    assert_eq!(packages[1].depends, None);
+}
+
+#[test]
+fn test_annotate_origins_falls_back_to_name() {
+    let mut packages = vec![make_test_package(
+        "mesa",
+        "24.2.1-1",
+        PackageRepository::Official,
+    )];
+    let sync = vec![make_db_package("mesa", "24.2.0-1", "extra", &[])];
+
+    annotate_origins(&mut packages, &sync);
+
+    assert_eq!(packages[0].sync_repo.as_deref(), Some("extra"));
+    // Another version's dependencies are not trusted
+    assert_eq!(packages[0].depends, None);
+}
+
+#[test]
+fn test_annotate_origins_skips_aur_packages() {
+    // An AUR package can share its name with a repository package
+    let mut packages = vec![make_test_package(
+        "mesa",
+        "24.2.1-1",
+        PackageRepository::Aur,
+    )];
+    let sync = vec![make_db_package("mesa", "24.2.1-1", "extra", &[])];
+
+    annotate_origins(&mut packages, &sync);
+
+    assert_eq!(packages[0].sync_repo, None);
+    assert_eq!(packages[0].depends, None);
+}
@@ -0,0 +1,148 @@
+use par_tui::core::dependency::build_required_by_index;
+use par_tui::core::size::annotate_sizes;
+use par_tui::io::pacman_db::{
+    list_sync_dbs, prepare_scratch_dbpath, read_local_db, read_sync_db, remove_scratch_dbpath,
+};
+use par_tui::models::package::{DbPackage, InstallReason, Package, PackageRepository};
+use par_tui::parser::pacman_db::parse_desc;
+use std::path::PathBuf;
+
+fn fixture_dbpath() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pacman_db")
+}
+
+fn load_local() -> Vec<DbPackage> {
+    let Ok(records) = read_local_db(&fixture_dbpath()) else {
+        panic!("local db fixture is not readable");
+    };
+    let mut packages: Vec<DbPackage> = records.iter().filter_map(|r| parse_desc(r)).collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+#[test]
+fn test_read_local_db() {
+    let local = load_local();
+
+    // ALPM_DB_VERSION is not a package entry
+    assert_eq!(local.len(), 3);
+    assert_eq!(local[0].name, "bash");
+    assert_eq!(local[1].name, "glibc");
+    assert_eq!(local[1].version, "2.40-1");
+    assert_eq!(local[1].install_reason, Some(InstallReason::Dependency));
+    assert_eq!(local[2].name, "hobby-app-git");
+    assert_eq!(local[2].install_reason, Some(InstallReason::Explicit));
+}
+
+#[test]
+fn test_read_local_db_missing_dbpath() {
+    let result = read_local_db(&fixture_dbpath().join("does-not-exist"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_list_and_read_sync_db() {
+    let Ok(dbs) = list_sync_dbs(&fixture_dbpath()) else {
+        panic!("sync dir fixture is not readable");
+    };
+    assert_eq!(dbs.len(), 1);
+    assert_eq!(dbs[0].0, "core");
+
+    let Ok(records) = read_sync_db(&dbs[0].1) else {
+        panic!("gzip sync db fixture is not readable");
+    };
+    let packages: Vec<DbPackage> = records.iter().filter_map(|r| parse_desc(r)).collect();
+
+    assert_eq!(packages.len(), 2);
+    let Some(glibc) = packages.iter().find(|p| p.name == "glibc") else {
+        panic!("glibc missing from sync db");
+    };
+    assert_eq!(glibc.version, "2.41-1");
+    assert_eq!(glibc.download_size, Some(10_462_512));
+    assert_eq!(glibc.installed_size, Some(49_120_834));
+    assert_eq!(glibc.install_reason, None);
+}
+
+#[test]
+fn test_read_zstd_sync_db() {
+    // Same archive as the gzip fixture, recompressed the way newer repos ship it
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/pacman_db_zstd/sync/core.db");
+    let Ok(records) = read_sync_db(&path) else {
+        panic!("zstd sync db fixture is not readable");
+    };
+    let packages: Vec<DbPackage> = records.iter().filter_map(|r| parse_desc(r)).collect();
+
+    assert_eq!(packages.len(), 2);
+    assert!(
+        packages
+            .iter()
+            .any(|p| p.name == "glibc" && p.version == "2.41-1")
+    );
+}
+
+#[test]
+fn test_required_by_from_local_db() {
+    let index = build_required_by_index(&load_local());
+
+    assert_eq!(index["glibc"], vec!["bash", "hobby-app-git"]);
+    assert_eq!(index["bash"], vec!["hobby-app-git"]);
+    assert!(index["hobby-app-git"].is_empty());
+}
+
+#[test]
+fn test_annotate_sizes_from_fixture_dbs() {
+    let Ok(dbs) = list_sync_dbs(&fixture_dbpath()) else {
+        panic!("sync dir fixture is not readable");
+    };
+    let Ok(records) = read_sync_db(&dbs[0].1) else {
+        panic!("gzip sync db fixture is not readable");
+    };
+    let sync: Vec<DbPackage> = records.iter().filter_map(|r| parse_desc(r)).collect();
+
+    let mut packages = vec![Package {
+        name: "glibc".to_string(),
+        current_version: Some("2.40-1".to_string()),
+        new_version: "2.41-1".to_string(),
+        repository: PackageRepository::Official,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
+    }];
+    annotate_sizes(&mut packages, &sync, &load_local());
+
+    assert_eq!(packages[0].download_size, Some(10_462_512));
+    assert_eq!(
+        packages[0].installed_size_delta,
+        Some(49_120_834 - 48_937_609)
+    );
+}
+
+#[test]
+fn test_prepare_scratch_dbpath_links_local_and_copies_sync() {
+    let scratch = std::env::temp_dir().join(format!("par_tui_scratch_{}", std::process::id()));
+    let missing = fixture_dbpath().join("missing");
+    let dbpath = fixture_dbpath();
+
+    // The first source without sync databases is skipped
+    let prepared = prepare_scratch_dbpath(&scratch, &dbpath, &[&missing, &dbpath]);
+    let local = read_local_db(&scratch).map(|records| records.len());
+    let synced = list_sync_dbs(&scratch).map(|dbs| dbs.len());
+    let linked = std::fs::symlink_metadata(scratch.join("local"))
+        .is_ok_and(|meta| meta.file_type().is_symlink());
+    remove_scratch_dbpath(&scratch);
+
+    assert!(prepared.is_ok());
+    assert!(linked);
+    assert_eq!(local.ok(), Some(3));
+    assert_eq!(
+        synced.ok(),
+        list_sync_dbs(&dbpath).map(|dbs| dbs.len()).ok()
+    );
+    assert!(!scratch.exists());
+    // The fixture's local database survives removing the link
+    assert_eq!(load_local().len(), 3);
+    assert!(prepare_scratch_dbpath(&scratch, &dbpath, &[&missing]).is_err());
+}
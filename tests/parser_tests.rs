@@ -1,5 +1,8 @@
-use par_tui::models::package::PackageRepository;
-use par_tui::parser::pacman;
+use par_tui::models::config::AurHelper;
+use par_tui::models::package::{InstallReason, PackageRepository, UpdateKind};
+use par_tui::parser::{
+    self, aurutils, df, pacman, pacman_conf, pacman_db, pikaur, srcinfo, toml, yay,
+};
 
 #[test]
 fn test_parse_checkupdates_single_package() {
@@ -81,6 +84,75 @@ Required By     : bash  systemd  pacman
     assert!(deps.contains(&"pacman".to_string()));
 }
 
+#[test]
+fn test_parse_package_relations() {
+    let relations =
+        pacman::parse_package_relations(include_str!("fixtures/info/pacman_qi_glibc.txt"));
+
+    assert_eq!(relations.provides, vec!["libc.so=6-64", "libm.so=6-64"]);
+    assert_eq!(
+        relations.depends,
+        vec!["linux-api-headers>=4.10", "tzdata", "filesystem"]
+    );
+    assert_eq!(
+        relations.optdepends,
+        vec!["gd: for memusagestat", "perl: for mtrace [installed]"]
+    );
+    // Wrapped continuation lines are part of the list
+    assert_eq!(relations.required_by.len(), 10);
+    assert_eq!(
+        relations.required_by.last().map(String::as_str),
+        Some("coreutils")
+    );
+}
+
+#[test]
+fn test_parse_required_by_records() {
+    // `pacman -Qi glibc missing bash`: the missing package has no record
+    let output = format!(
+        "{}Name            : bash
+Version         : 5.2.037-1
+Required By     : base  bzip2
+                  gzip
+
+",
+        include_str!("fixtures/info/pacman_qi_glibc.txt")
+    );
+    let records = pacman::parse_required_by_records(&output);
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records["glibc"].len(), 10);
+    assert_eq!(records["bash"], vec!["base", "bzip2", "gzip"]);
+    assert!(!records.contains_key("missing"));
+}
+
+#[test]
+fn test_parse_required_by_records_none() {
+    let output = "Name            : zstd\nRequired By     : None\n\n";
+    let records = pacman::parse_required_by_records(output);
+
+    assert_eq!(records.get("zstd"), Some(&Vec::new()));
+    assert!(pacman::parse_required_by_records("").is_empty());
+}
+
+#[test]
+fn test_parse_print_output() {
+    let output = "\
+:: Replace jack2 with extra/pipewire-jack? [Y/n]
+glibc 2.41-1 core 10462512
+pipewire-jack 1:1.2.7-1 extra 0
+";
+    let targets = pacman::parse_print_output(output);
+
+    assert_eq!(targets.len(), 2);
+    assert_eq!(targets[0].name, "glibc");
+    assert_eq!(targets[0].version, "2.41-1");
+    assert_eq!(targets[0].repository, "core");
+    assert_eq!(targets[0].download_size, 10_462_512);
+    // Already cached packages have nothing left to download
+    assert_eq!(targets[1].download_size, 0);
+}
+
 #[test]
 fn test_parse_required_by_not_found() {
     let output = r"Name            : test-package
@@ -89,3 +161,414 @@ Version         : 1.0.0-1
     let deps = pacman::parse_required_by(output);
     assert!(deps.is_empty());
 }
+
+#[test]
+fn test_parse_yay_output() {
+    let output = "google-chrome 120.0.1-1 -> 121.0.2-1\nyay-bin 12.0.1-1 -> 12.0.2-1\n";
+    let packages = yay::parse_yay_output(output);
+
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0].name, "google-chrome");
+    assert_eq!(packages[1].new_version, "12.0.2-1");
+    assert!(
+        packages
+            .iter()
+            .all(|p| p.repository == PackageRepository::Aur)
+    );
+}
+
+#[test]
+fn test_parse_yay_output_skips_status_lines() {
+    let output = r" -> Searching AUR for updates...
+ -> Missing AUR Debug Packages: foo-debug bar-debug
+google-chrome 120.0.1-1 -> 121.0.2-1
+";
+    let packages = yay::parse_yay_output(output);
+
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].name, "google-chrome");
+}
+
+#[test]
+fn test_parse_pikaur_output_skips_status_lines() {
+    let output = r":: Reading AUR package info...
+aur/hobby-app-git r12.a-1 -> r13.b-1
+pikaur 1.20-1 -> 1.21-1
+";
+    let packages = pikaur::parse_pikaur_output(output);
+
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0].name, "hobby-app-git");
+    assert_eq!(packages[0].current_version, Some("r12.a-1".to_string()));
+    assert_eq!(packages[1].name, "pikaur");
+}
+
+#[test]
+fn test_parse_aurutils_output_tab_separated() {
+    let output = "aurutils\t18.5-1\t->\t19.0-1\n";
+    let packages = aurutils::parse_aurutils_output(output);
+
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].name, "aurutils");
+    assert_eq!(packages[0].new_version, "19.0-1");
+    assert_eq!(packages[0].repository, PackageRepository::Aur);
+}
+
+#[test]
+fn test_parse_aur_query_dispatches_by_helper() {
+    let output = "aurutils\t18.5-1\t->\t19.0-1\n";
+
+    let packages = parser::parse_aur_query(AurHelper::Aurutils, output);
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].name, "aurutils");
+
+    let packages = parser::parse_aur_query(AurHelper::Yay, "yay 12.3.0-1 -> 12.4.2-1\n");
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].new_version, "12.4.2-1");
+}
+
+#[test]
+fn test_parse_desc_local_record() {
+    let desc = include_str!("fixtures/pacman_db/local/glibc-2.40-1/desc");
+    let Some(package) = pacman_db::parse_desc(desc) else {
+        panic!("desc record should parse");
+    };
+
+    assert_eq!(package.name, "glibc");
+    assert_eq!(package.version, "2.40-1");
+    assert_eq!(
+        package.depends,
+        vec!["linux-api-headers>=4.10", "tzdata", "filesystem"]
+    );
+    assert_eq!(package.provides, vec!["libc.so=6-64"]);
+    assert_eq!(package.installed_size, Some(48_937_609));
+    assert_eq!(package.download_size, None);
+    assert_eq!(package.install_reason, Some(InstallReason::Dependency));
+}
+
+#[test]
+fn test_parse_desc_explicit_when_reason_absent() {
+    let desc = include_str!("fixtures/pacman_db/local/hobby-app-git-r12.a-1/desc");
+    let Some(package) = pacman_db::parse_desc(desc) else {
+        panic!("desc record should parse");
+    };
+
+    assert_eq!(package.install_reason, Some(InstallReason::Explicit));
+}
+
+#[test]
+fn test_parse_desc_replaces() {
+    let desc = "%NAME%\npipewire-jack\n\n%VERSION%\n1:1.2.7-1\n\n%REPLACES%\njack2\njack<1\n\n";
+    let Some(package) = pacman_db::parse_desc(desc) else {
+        panic!("desc record should parse");
+    };
+
+    assert_eq!(package.replaces, vec!["jack2", "jack<1"]);
+}
+
+#[test]
+fn test_parse_desc_conflicts() {
+    let desc = "%NAME%\npipewire-jack\n\n%VERSION%\n1:1.2.7-1\n\n%CONFLICTS%\njack\njack2\n\n";
+    let Some(package) = pacman_db::parse_desc(desc) else {
+        panic!("desc record should parse");
+    };
+
+    assert_eq!(package.conflicts, vec!["jack", "jack2"]);
+}
+
+#[test]
+fn test_parse_desc_groups() {
+    let desc = "%NAME%\nnautilus\n\n%VERSION%\n47.1-1\n\n%GROUPS%\ngnome\ngnome-extra\n\n";
+    let Some(package) = pacman_db::parse_desc(desc) else {
+        panic!("desc record should parse");
+    };
+
+    assert_eq!(package.groups, vec!["gnome", "gnome-extra"]);
+    assert_eq!(package.repository, None);
+}
+
+#[test]
+fn test_parse_desc_missing_name() {
+    let desc = "%VERSION%\n1.0-1\n\n";
+    assert!(pacman_db::parse_desc(desc).is_none());
+}
+
+#[test]
+fn test_parse_config_auto_ignore_rules() {
+    let content = r#"
+[[exclude.auto_ignore]]
+kind = "pkgrel"
+repository = "aur"
+
+[[exclude.auto_ignore]]
+kind = "downgrade"
+"#;
+    let Ok(config) = toml::parse_config(content) else {
+        panic!("config should parse");
+    };
+
+    let rules = &config.exclude.auto_ignore;
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].kind, UpdateKind::PkgrelOnly);
+    assert_eq!(rules[0].repository, Some(PackageRepository::Aur));
+    assert_eq!(rules[1].kind, UpdateKind::Downgrade);
+    assert_eq!(rules[1].repository, None);
+}
+
+#[test]
+fn test_parse_package_info_sync() {
+    let details = pacman::parse_package_info(include_str!("fixtures/info/pacman_si_glibc.txt"));
+
+    // Only the first repository's record is read
+    assert_eq!(details.repository, Some("core".to_string()));
+    assert_eq!(details.version, Some("2.41-1".to_string()));
+    assert_eq!(details.description, Some("GNU C Library".to_string()));
+    assert_eq!(
+        details.url,
+        Some("https://www.gnu.org/software/libc".to_string())
+    );
+    assert_eq!(
+        details.licenses,
+        vec!["GPL-2.0-or-later", "LGPL-2.1-or-later"]
+    );
+    assert_eq!(
+        details.depends,
+        vec!["linux-api-headers>=4.10", "tzdata", "filesystem"]
+    );
+    assert_eq!(
+        details.optdepends,
+        vec!["gd: for memusagestat", "perl: for mtrace"]
+    );
+    assert_eq!(details.download_size, Some(10_464_788));
+    assert_eq!(details.installed_size, Some(49_115_299));
+    assert_eq!(
+        details.build_date,
+        Some("Fri 31 Jan 2025 10:12:43 AM UTC".to_string())
+    );
+    assert_eq!(
+        details.packager,
+        Some("Frederik Schwan <freswa@archlinux.org>".to_string())
+    );
+}
+
+#[test]
+fn test_parse_package_info_none_fields() {
+    let output = "Name            : foo\nVersion         : 1.0-1\nDepends On      : None\nOptional Deps   : None\nInstalled Size  : 512.00 B\n";
+    let details = pacman::parse_package_info(output);
+
+    assert!(details.depends.is_empty());
+    assert!(details.optdepends.is_empty());
+    assert_eq!(details.installed_size, Some(512));
+}
+
+#[test]
+fn test_parse_srcinfo_package_overrides_base() {
+    let content = include_str!("fixtures/info/SRCINFO_split");
+    let Some(details) = srcinfo::parse_srcinfo(content, "foo-tools") else {
+        panic!("foo-tools should be found");
+    };
+
+    assert_eq!(details.version, Some("1:1.4.0-2".to_string()));
+    assert_eq!(details.description, Some("Tools for foo".to_string()));
+    assert_eq!(details.url, Some("https://example.org/foo".to_string()));
+    assert_eq!(details.licenses, vec!["MIT"]);
+    assert_eq!(details.depends, vec!["foo-libs"]);
+    assert_eq!(details.optdepends, vec!["python: for scripts"]);
+}
+
+#[test]
+fn test_parse_srcinfo_inherits_base() {
+    let content = include_str!("fixtures/info/SRCINFO_split");
+    let Some(details) = srcinfo::parse_srcinfo(content, "foo-libs") else {
+        panic!("foo-libs should be found");
+    };
+
+    assert_eq!(details.description, Some("Libraries for foo".to_string()));
+    assert_eq!(details.depends, vec!["glibc", "zlib"]);
+    assert!(srcinfo::parse_srcinfo(content, "missing").is_none());
+}
+
+#[test]
+fn test_parse_df_output() {
+    let output =
+        "Mounted on          Avail\n/             21474836480\n/mnt/my disk   1073741824\n";
+    let spaces = df::parse_df_output(output);
+
+    assert_eq!(spaces.len(), 2);
+    assert_eq!(spaces[0].mount_point, "/");
+    assert_eq!(spaces[0].available, 21_474_836_480);
+    assert_eq!(spaces[1].mount_point, "/mnt/my disk");
+    assert_eq!(spaces[1].available, 1_073_741_824);
+}
+
+#[test]
+fn test_parse_config_holds() {
+    let content = r#"
+[exclude]
+permanent = ["*-git"]
+
+[[exclude.hold]]
+name = "linux-lts"
+version = ">=6.7"
+"#;
+    let Ok(config) = toml::parse_config(content) else {
+        panic!("config should parse");
+    };
+
+    assert_eq!(config.exclude.permanent, vec!["*-git".into()]);
+    assert_eq!(config.exclude.hold.len(), 1);
+    assert_eq!(config.exclude.hold[0].name, "linux-lts");
+    assert_eq!(config.exclude.hold[0].version, ">=6.7");
+}
+
+#[test]
+fn test_parse_config_annotated_permanent() {
+    let content = r#"
+[exclude]
+permanent = [
+    "*-git",
+    { name = "linux-lts", note = "vendor driver", added = "2026-10-17" },
+    { name = "mesa" },
+]
+"#;
+    let Ok(config) = toml::parse_config(content) else {
+        panic!("config should parse");
+    };
+
+    assert_eq!(config.exclude.permanent.len(), 3);
+    assert_eq!(config.exclude.permanent[0], "*-git".into());
+    assert_eq!(config.exclude.permanent[1].name, "linux-lts");
+    assert_eq!(
+        config.exclude.permanent[1].note.as_deref(),
+        Some("vendor driver")
+    );
+    assert_eq!(
+        config.exclude.permanent[1].added.as_deref(),
+        Some("2026-10-17")
+    );
+    assert_eq!(config.exclude.permanent[2], "mesa".into());
+}
+
+#[test]
+fn test_serialize_config_permanent_round_trip() {
+    let content = r#"
+[exclude]
+permanent = ["*-git", { name = "linux-lts", note = "vendor driver" }]
+"#;
+    let Ok(config) = toml::parse_config(content) else {
+        panic!("config should parse");
+    };
+    let Ok(serialized) = toml::serialize_config(&config) else {
+        panic!("config should serialize");
+    };
+    let Ok(reparsed) = toml::parse_config(&serialized) else {
+        panic!("serialized config should parse: {serialized}");
+    };
+
+    // Plain entries stay plain strings
+    assert!(serialized.contains(r#""*-git""#));
+    assert!(!serialized.contains("added"));
+    assert_eq!(reparsed.exclude.permanent, config.exclude.permanent);
+}
+
+#[test]
+fn test_parse_config_snoozes() {
+    let content = r#"
+[behavior]
+snooze_days = 3
+
+[[exclude.snooze]]
+name = "mesa"
+until = "2026-10-24T09:30:00Z"
+"#;
+    let Ok(config) = toml::parse_config(content) else {
+        panic!("config should parse");
+    };
+
+    assert_eq!(config.behavior.snooze_days, 3);
+    assert_eq!(config.exclude.snooze.len(), 1);
+    assert_eq!(config.exclude.snooze[0].name, "mesa");
+    assert_eq!(config.exclude.snooze[0].until, "2026-10-24T09:30:00Z");
+}
+
+#[test]
+fn test_parse_config_simulate_transaction() {
+    let Ok(defaults) = toml::parse_config("") else {
+        panic!("empty config should parse");
+    };
+    let Ok(config) = toml::parse_config("[behavior]\nsimulate_transaction = true\n") else {
+        panic!("config should parse");
+    };
+    assert!(!defaults.behavior.simulate_transaction);
+    assert!(config.behavior.simulate_transaction);
+}
+
+#[test]
+fn test_parse_config_transitive_holds() {
+    let Ok(defaults) = toml::parse_config("") else {
+        panic!("empty config should parse");
+    };
+    let Ok(config) = toml::parse_config("[behavior]\ntransitive_holds = false\n") else {
+        panic!("config should parse");
+    };
+
+    assert!(defaults.behavior.transitive_holds);
+    assert!(!config.behavior.transitive_holds);
+}
+
+fn fixture_include(pattern: &str) -> Vec<String> {
+    match pattern {
+        "/etc/pacman.d/*.conf" => {
+            vec![include_str!("fixtures/pacman_conf/ignore.conf").to_string()]
+        },
+        "/etc/pacman.d/mirrorlist" => {
+            vec![include_str!("fixtures/pacman_conf/mirrorlist").to_string()]
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn test_parse_pacman_conf() {
+    let content = include_str!("fixtures/pacman_conf/pacman.conf");
+    let mut includes = Vec::new();
+    let conf = pacman_conf::parse_pacman_conf(content, |pattern| {
+        includes.push(pattern.to_string());
+        fixture_include(pattern)
+    });
+
+    assert_eq!(
+        conf.ignore_pkg,
+        vec!["linux", "linux-headers", "nvidia-*", "zoom"]
+    );
+    assert_eq!(conf.ignore_group, vec!["gnome", "kde-applications"]);
+    assert_eq!(conf.repositories, vec!["core", "extra", "multilib"]);
+    assert_eq!(
+        conf.cache_dirs,
+        vec!["/mnt/cache/pacman/pkg/", "/var/cache/pacman/pkg/"]
+    );
+    // Commented-out sections are not followed
+    assert_eq!(includes.len(), 4);
+}
+
+#[test]
+fn test_parse_pacman_conf_ignores_directives_outside_options() {
+    let content = "[custom]\nIgnorePkg = foo\nServer = file:///srv/repo\n";
+    let conf = pacman_conf::parse_pacman_conf(content, |_| Vec::new());
+
+    assert!(conf.ignore_pkg.is_empty());
+    assert_eq!(conf.repositories, vec!["custom"]);
+}
+
+#[test]
+fn test_parse_pacman_conf_include_loop() {
+    let content = "[options]\nInclude = /etc/pacman.d/self.conf\n";
+    let mut calls = 0;
+    let conf = pacman_conf::parse_pacman_conf(content, |_| {
+        calls += 1;
+        vec!["IgnorePkg = foo\nInclude = /etc/pacman.d/self.conf\n".to_string()]
+    });
+
+    assert_eq!(calls, 10);
+    assert_eq!(conf.ignore_pkg.len(), 10);
+}
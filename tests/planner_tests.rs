@@ -1,6 +1,7 @@
-use par_tui::core::planner::{UpdateMode, create_plan};
-use par_tui::models::config::Config;
+use par_tui::core::planner::{UpdateMode, cache_cleanup_command, create_plan};
+use par_tui::models::config::{Config, DEFAULT_CACHEDIR};
 use par_tui::models::package::{Package, PackageRepository};
+use std::path::Path;
 
 fn make_test_package(name: &str, repo: PackageRepository) -> Package {
     Package {
@@ -8,6 +9,11 @@ fn make_test_package(name: &str, repo: PackageRepository) -> Package {
         current_version: Some("1.0.0".to_string()),
         new_version: "2.0.0".to_string(),
         repository: repo,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
     }
 }
 
@@ -91,3 +97,55 @@ fn test_build_command_with_extra_args() {
 
     assert!(cmd.contains(&"--noconfirm".to_string()));
 }
+
+#[test]
+fn test_cache_cleanup_command() {
+    let cmd = cache_cleanup_command(Path::new(DEFAULT_CACHEDIR));
+
+    assert_eq!(
+        cmd,
+        vec![
+            "sudo",
+            "paccache",
+            "-r",
+            "-k",
+            "1",
+            "-c",
+            "/var/cache/pacman/pkg"
+        ]
+    );
+}
+
+#[test]
+fn test_simulate_command() {
+    let mut plan = create_plan(
+        UpdateMode::EntireSystem,
+        vec![make_test_package("pkg1", PackageRepository::Official)],
+        vec!["linux".to_string(), "mesa".to_string()],
+    );
+    plan.ignore_groups = vec!["gnome".to_string()];
+
+    let cmd = plan.simulate_command(Path::new("/tmp/par_tui-simulate-42"));
+
+    assert_eq!(
+        cmd,
+        vec![
+            "fakeroot",
+            "--",
+            "pacman",
+            "-Syu",
+            "--print",
+            "--print-format",
+            "%n %v %r %s",
+            "--noconfirm",
+            "--dbpath",
+            "/tmp/par_tui-simulate-42",
+            "--logfile",
+            "/dev/null",
+            "--ignore",
+            "linux,mesa",
+            "--ignoregroup",
+            "gnome"
+        ]
+    );
+}
@@ -0,0 +1,113 @@
+use par_tui::core::preflight::{
+    SPACE_MARGIN, SpaceVerdict, check_disk_space, evaluate_free_space, uncached_download,
+};
+use par_tui::models::disk::FilesystemSpace;
+use par_tui::models::package::{Package, PackageRepository};
+
+const MIB: u64 = 1024 * 1024;
+const MIB_SIGNED: i64 = 1024 * 1024;
+
+fn make_fs(mount_point: &str, available: u64) -> FilesystemSpace {
+    FilesystemSpace {
+        mount_point: mount_point.to_string(),
+        available,
+    }
+}
+
+fn make_package(name: &str, new_version: &str, download_size: u64) -> Package {
+    Package {
+        name: name.to_string(),
+        current_version: Some("1.0-1".to_string()),
+        new_version: new_version.to_string(),
+        repository: PackageRepository::Official,
+        download_size: Some(download_size),
+        installed_size_delta: Some(0),
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
+    }
+}
+
+#[test]
+fn test_enough_space() {
+    let cache = make_fs("/var", 10_000 * MIB);
+    let root = make_fs("/", 10_000 * MIB);
+
+    assert!(check_disk_space(500 * MIB, 300 * MIB_SIGNED, &cache, &root).is_empty());
+}
+
+#[test]
+fn test_cache_short_on_separate_filesystem() {
+    let cache = make_fs("/var", 400 * MIB);
+    let root = make_fs("/", 10_000 * MIB);
+
+    let shortages = check_disk_space(500 * MIB, 300 * MIB_SIGNED, &cache, &root);
+
+    assert_eq!(shortages.len(), 1);
+    assert_eq!(shortages[0].mount_point, "/var");
+    assert_eq!(shortages[0].purpose, "package cache");
+    assert_eq!(shortages[0].required, 500 * MIB + SPACE_MARGIN);
+    assert_eq!(shortages[0].available, 400 * MIB);
+}
+
+#[test]
+fn test_shared_filesystem_adds_requirements() {
+    // Each requirement fits on its own, but not both together
+    let fs = make_fs("/", 800 * MIB);
+
+    let shortages = check_disk_space(500 * MIB, 300 * MIB_SIGNED, &fs, &fs);
+
+    assert_eq!(shortages.len(), 1);
+    assert_eq!(shortages[0].required, 800 * MIB + SPACE_MARGIN);
+}
+
+#[test]
+fn test_shrinking_install_needs_no_root_space() {
+    let cache = make_fs("/var", 10_000 * MIB);
+    let root = make_fs("/", 0);
+
+    assert!(check_disk_space(500 * MIB, -200 * MIB_SIGNED, &cache, &root).is_empty());
+}
+
+#[test]
+fn test_evaluate_free_space() {
+    let cache = make_fs("/var", 400 * MIB);
+    let root = make_fs("/", 10_000 * MIB);
+
+    assert_eq!(
+        evaluate_free_space(100 * MIB, 0, &[cache.clone(), root.clone()]),
+        SpaceVerdict::Sufficient
+    );
+    let SpaceVerdict::Short(shortages) = evaluate_free_space(500 * MIB, 0, &[cache, root]) else {
+        panic!("cache should be short");
+    };
+    assert_eq!(shortages[0].purpose, "package cache");
+}
+
+#[test]
+fn test_evaluate_free_space_unexpected_df_output() {
+    let root = make_fs("/", 10_000 * MIB);
+
+    assert!(matches!(
+        evaluate_free_space(500 * MIB, 0, &[root]),
+        SpaceVerdict::Unknown(_)
+    ));
+}
+
+#[test]
+fn test_uncached_download_skips_cached_packages() {
+    let packages = [
+        make_package("linux", "6.11.1.arch1-1", 140 * MIB),
+        make_package("python", "3.12.7-1", 30 * MIB),
+        make_package("vim", "9.1.0785-1", 2 * MIB),
+    ];
+    let cache_files = [
+        "linux-6.11.1.arch1-1-x86_64.pkg.tar.zst",
+        // An older version and a lone signature do not count
+        "python-3.12.6-1-x86_64.pkg.tar.zst",
+        "vim-9.1.0785-1-x86_64.pkg.tar.zst.sig",
+    ]
+    .map(String::from);
+
+    assert_eq!(uncached_download(&packages, &cache_files), 32 * MIB);
+}
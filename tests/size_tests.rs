@@ -0,0 +1,73 @@
+use par_tui::core::size::{annotate_sizes, size_totals};
+use par_tui::models::package::{DbPackage, Package, PackageRepository};
+
+fn make_test_package(name: &str, current: &str, new: &str) -> Package {
+    Package {
+        name: name.to_string(),
+        current_version: Some(current.to_string()),
+        new_version: new.to_string(),
+        repository: PackageRepository::Official,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
+    }
+}
+
+fn make_db_package(name: &str, version: &str, download: u64, installed: u64) -> DbPackage {
+    DbPackage {
+        name: name.to_string(),
+        version: version.to_string(),
+        download_size: Some(download),
+        installed_size: Some(installed),
+        ..DbPackage::default()
+    }
+}
+
+#[test]
+fn test_annotate_sizes_matching_version() {
+    let mut packages = vec![make_test_package("mesa", "24.0-1", "24.1-1")];
+    let sync = vec![make_db_package("mesa", "24.1-1", 30_000, 100_000)];
+    let local = vec![make_db_package("mesa", "24.0-1", 0, 120_000)];
+
+    annotate_sizes(&mut packages, &sync, &local);
+
+    assert_eq!(packages[0].download_size, Some(30_000));
+    assert_eq!(packages[0].installed_size_delta, Some(-20_000));
+}
+
+#[test]
+fn test_annotate_sizes_ignores_stale_sync_record() {
+    let mut packages = vec![make_test_package("mesa", "24.0-1", "24.1-1")];
+    let sync = vec![make_db_package("mesa", "24.0-1", 30_000, 100_000)];
+
+    annotate_sizes(&mut packages, &sync, &[]);
+
+    assert_eq!(packages[0].download_size, None);
+    assert_eq!(packages[0].installed_size_delta, None);
+}
+
+#[test]
+fn test_annotate_sizes_without_local_record() {
+    let mut packages = vec![make_test_package("mesa", "24.0-1", "24.1-1")];
+    let sync = vec![make_db_package("mesa", "24.1-1", 30_000, 100_000)];
+
+    annotate_sizes(&mut packages, &sync, &[]);
+
+    assert_eq!(packages[0].download_size, Some(30_000));
+    assert_eq!(packages[0].installed_size_delta, None);
+}
+
+#[test]
+fn test_size_totals_counts_unsized_packages() {
+    let mut sized = make_test_package("a", "1-1", "2-1");
+    sized.download_size = Some(1_000);
+    sized.installed_size_delta = Some(-300);
+    let mut other = make_test_package("b", "1-1", "2-1");
+    other.download_size = Some(500);
+    other.installed_size_delta = Some(800);
+    let missing = make_test_package("c", "1-1", "2-1");
+
+    assert_eq!(size_totals(&[sized, other, missing]), (1_500, 500, 1));
+}
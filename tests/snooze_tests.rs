@@ -0,0 +1,80 @@
+use par_tui::core::snooze::{expiry_after, partition_snoozes};
+use par_tui::core::time::{format_date, format_timestamp, parse_timestamp};
+use par_tui::models::config::SnoozeRule;
+
+fn snooze(name: &str, until: &str) -> SnoozeRule {
+    SnoozeRule {
+        name: name.to_string(),
+        until: until.to_string(),
+    }
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_timestamp(1_792_834_245), "2026-10-24T09:30:45Z");
+}
+
+#[test]
+fn test_format_date() {
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(1_792_834_245), "2026-10-24");
+}
+
+#[test]
+fn test_parse_timestamp() {
+    assert_eq!(parse_timestamp("1970-01-01"), Some(0));
+    assert_eq!(parse_timestamp("2000-02-29"), Some(951_782_400));
+    assert_eq!(parse_timestamp("2026-10-24T09:30:45Z"), Some(1_792_834_245));
+}
+
+#[test]
+fn test_parse_timestamp_rejects_malformed() {
+    for value in [
+        "",
+        "2026-10",
+        "2026-13-01",
+        "2026-02-29",
+        "2026-10-24T09:30:45",
+        "2026-10-24T24:00:00Z",
+        "2026-10-24 09:30:45Z",
+        "+2026-10-24",
+        "1969-12-31",
+        "0000-01-15",
+        "0000-03-01",
+    ] {
+        assert_eq!(parse_timestamp(value), None, "{value}");
+    }
+}
+
+#[test]
+fn test_timestamp_round_trip() {
+    for secs in [0, 86_399, 951_868_799, 4_102_444_800, 1_792_834_245] {
+        assert_eq!(parse_timestamp(&format_timestamp(secs)), Some(secs));
+    }
+}
+
+#[test]
+fn test_expiry_after() {
+    assert_eq!(expiry_after(1_792_834_245, 7), "2026-10-31T09:30:45Z");
+}
+
+#[test]
+fn test_partition_snoozes() {
+    let now = 1_792_834_245; // 2026-10-24T09:30:45Z
+    let snoozes = vec![
+        snooze("mesa", "2026-10-31"),
+        snooze("linux", "2026-10-24T09:30:45Z"),
+        snooze("nvidia", "2026-10-01"),
+        snooze("typo", "next week"),
+    ];
+
+    let (active, expired) = partition_snoozes(&snoozes, now);
+
+    assert_eq!(active, vec![snooze("mesa", "2026-10-31")]);
+    assert_eq!(
+        expired.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        vec!["linux", "nvidia", "typo"]
+    );
+}
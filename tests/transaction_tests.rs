@@ -0,0 +1,152 @@
+use par_tui::core::transaction::build_preview;
+use par_tui::models::package::{DbPackage, Package, PackageRepository};
+use par_tui::models::transaction::{TransactionAction, TransactionTarget};
+
+fn make_package(name: &str, repo: PackageRepository) -> Package {
+    Package {
+        name: name.to_string(),
+        current_version: Some("1.0-1".to_string()),
+        new_version: "2.0-1".to_string(),
+        repository: repo,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
+    }
+}
+
+fn make_target(name: &str, version: &str) -> TransactionTarget {
+    TransactionTarget {
+        name: name.to_string(),
+        version: version.to_string(),
+        repository: "extra".to_string(),
+        download_size: 1024,
+    }
+}
+
+fn make_db_package(name: &str, version: &str, replaces: &[&str]) -> DbPackage {
+    DbPackage {
+        name: name.to_string(),
+        version: version.to_string(),
+        replaces: replaces.iter().map(ToString::to_string).collect(),
+        ..DbPackage::default()
+    }
+}
+
+#[test]
+fn test_preview_classifies_targets() {
+    let packages = vec![make_package("mesa", PackageRepository::Official)];
+    let local = vec![
+        make_db_package("mesa", "1.0-1", &[]),
+        make_db_package("jack2", "1.9-1", &[]),
+    ];
+    let sync = vec![
+        make_db_package("mesa", "2.0-1", &[]),
+        make_db_package("pipewire-jack", "1.2-1", &["jack2", "jack<1"]),
+        make_db_package("libnew", "0.1-1", &[]),
+    ];
+    let targets = vec![
+        make_target("mesa", "2.0-1"),
+        make_target("pipewire-jack", "1.2-1"),
+        make_target("libnew", "0.1-1"),
+    ];
+
+    let preview = build_preview(&targets, &packages, &[], &local, &sync);
+
+    assert_eq!(
+        preview.entries[0].action,
+        TransactionAction::Upgrade {
+            from: "1.0-1".to_string()
+        }
+    );
+    // Only installed packages are replaced; `jack` is not installed
+    assert_eq!(
+        preview.entries[1].action,
+        TransactionAction::Replace {
+            replaces: vec!["jack2".to_string()]
+        }
+    );
+    assert_eq!(preview.entries[2].action, TransactionAction::Install);
+    assert_eq!(preview.removals, vec!["jack2"]);
+    assert!(preview.skipped.is_empty());
+}
+
+#[test]
+fn test_preview_reports_skipped_updates() {
+    let packages = vec![
+        make_package("linux", PackageRepository::Official),
+        make_package("mesa", PackageRepository::Official),
+        make_package("jack2", PackageRepository::Official),
+        make_package("yay", PackageRepository::Aur),
+        make_package("firefox", PackageRepository::Official),
+    ];
+    let local = vec![
+        make_db_package("mesa", "1.0-1", &[]),
+        make_db_package("jack2", "1.0-1", &[]),
+    ];
+    let sync = vec![make_db_package("pipewire-jack", "1.2-1", &["jack2"])];
+    let targets = vec![
+        make_target("mesa", "2.0-1"),
+        make_target("pipewire-jack", "1.2-1"),
+    ];
+    let ignored = vec!["linux".to_string()];
+
+    let preview = build_preview(&targets, &packages, &ignored, &local, &sync);
+
+    // Ignored, AUR and replaced packages are expected to be missing
+    assert_eq!(preview.skipped, vec!["firefox"]);
+}
+
+fn make_conflicting(name: &str, version: &str, conflicts: &[&str]) -> DbPackage {
+    DbPackage {
+        conflicts: conflicts.iter().map(ToString::to_string).collect(),
+        ..make_db_package(name, version, &[])
+    }
+}
+
+#[test]
+fn test_preview_infers_conflict_removals() {
+    let packages = vec![
+        make_package("openssl", PackageRepository::Official),
+        make_package("libressl", PackageRepository::Official),
+    ];
+    let mut provider = make_db_package("busybox-vi", "1.36-1", &[]);
+    provider.provides = vec!["vi".to_string()];
+    let local = vec![
+        make_db_package("openssl", "1.0-1", &[]),
+        make_db_package("libressl", "1.0-1", &[]),
+        make_conflicting("iptables-legacy", "1.8-1", &["iptables"]),
+        provider,
+        make_db_package("vim", "9.1-1", &[]),
+    ];
+    let sync = vec![
+        // Conflicts with an upgraded target are resolved by the upgrade
+        make_conflicting("openssl", "2.0-1", &["libressl"]),
+        make_db_package("libressl", "2.0-1", &[]),
+        make_db_package("iptables", "1.8-2", &[]),
+        make_conflicting("neovim", "0.12-1", &["vi", "vim<9"]),
+    ];
+    let targets = vec![
+        make_target("openssl", "2.0-1"),
+        make_target("libressl", "2.0-1"),
+        make_target("iptables", "1.8-2"),
+        make_target("neovim", "0.12-1"),
+    ];
+
+    let preview = build_preview(&targets, &packages, &[], &local, &sync);
+
+    // Conflicts match provisions and the installed package's own conflicts
+    // count too; vim 9.1 is not `vim<9`
+    assert_eq!(preview.removals, vec!["busybox-vi", "iptables-legacy"]);
+    assert!(preview.skipped.is_empty());
+}
+
+#[test]
+fn test_preview_empty_transaction() {
+    let preview = build_preview(&[], &[], &[], &[], &[]);
+
+    assert!(preview.entries.is_empty());
+    assert!(preview.removals.is_empty());
+    assert!(preview.skipped.is_empty());
+}
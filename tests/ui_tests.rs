@@ -1,5 +1,18 @@
-use par_tui::models::package::{Package, PackageRepository};
-use par_tui::ui::app::AppState;
+use par_tui::core::dependency::{
+    ConstraintViolation, DependencyConflict, DependencyProblems, HoldNode, LibraryBreakage,
+};
+use par_tui::models::config::{
+    AutoIgnoreRule, ExcludeConfig, HoldRule, PermanentExclude, SnoozeRule,
+};
+use par_tui::models::package::{
+    Package, PackageDetails, PackageRelations, PackageRepository, ScanSource, UpdateKind,
+};
+use par_tui::models::pacman_conf::PacmanConf;
+use par_tui::ui::app::{AppState, GraphSection, IgnoreSet, repository_label};
+use std::collections::HashMap;
+
+/// 2026-10-24T09:30:45Z
+const NOW: u64 = 1_792_834_245;
 
 fn make_test_package(name: &str, repo: PackageRepository) -> Package {
     Package {
@@ -7,9 +20,22 @@ fn make_test_package(name: &str, repo: PackageRepository) -> Package {
         current_version: Some("1.0.0".to_string()),
         new_version: "2.0.0".to_string(),
         repository: repo,
+        download_size: None,
+        installed_size_delta: None,
+        sync_repo: None,
+        groups: Vec::new(),
+        depends: None,
     }
 }
 
+fn permanent_names(state: &AppState) -> Vec<&str> {
+    state
+        .permanent_rules
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect()
+}
+
 #[test]
 fn test_app_state_creation() {
     let packages = vec![
@@ -197,11 +223,11 @@ fn test_get_permanent_excludes() {
     // Toggle pkg1 to permanent ignore
     state.toggle_permanent_ignore();
 
-    let permanent_list = state.get_permanent_excludes();
+    let permanent_list = permanent_names(&state);
 
     assert_eq!(permanent_list.len(), 2);
-    assert!(permanent_list.contains(&"pkg1".to_string()));
-    assert!(permanent_list.contains(&"pkg2".to_string()));
+    assert!(permanent_list.contains(&"pkg1"));
+    assert!(permanent_list.contains(&"pkg2"));
 }
 
 #[test]
@@ -216,7 +242,7 @@ fn test_has_official_scan_failed_no_warnings() {
 fn test_has_official_scan_failed_with_official_failure() {
     let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
     let mut state = AppState::new(packages, &[]);
-    state.scan_warnings.push("Official scan failed".to_string());
+    state.add_scan_warning(ScanSource::Official, "Official scan failed");
 
     assert!(state.has_official_scan_failed());
 }
@@ -225,7 +251,7 @@ fn test_has_official_scan_failed_with_official_failure() {
 fn test_has_official_scan_failed_with_aur_failure_only() {
     let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
     let mut state = AppState::new(packages, &[]);
-    state.scan_warnings.push("AUR scan failed".to_string());
+    state.add_scan_warning(ScanSource::Aur, "AUR scan failed");
 
     assert!(!state.has_official_scan_failed());
 }
@@ -234,29 +260,28 @@ fn test_has_official_scan_failed_with_aur_failure_only() {
 fn test_has_official_scan_failed_with_combined_failure() {
     let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
     let mut state = AppState::new(packages, &[]);
-    state
-        .scan_warnings
-        .push("Official & AUR scan failed".to_string());
+    state.add_scan_warning(ScanSource::Official, "Official scan failed");
+    state.add_scan_warning(ScanSource::Aur, "AUR scan failed");
 
-    // Should detect "Official" marker even in combined message
+    // Should detect the official failure regardless of other sources
     assert!(state.has_official_scan_failed());
+    assert_eq!(state.scan_warnings.len(), 2);
 }
 
 #[test]
-fn test_scan_failure_marker_constants() {
-    use par_tui::io::terminal::{AUR_SCAN_FAILURE_MARKER, OFFICIAL_SCAN_FAILURE_MARKER};
+fn test_scan_warning_message_does_not_affect_source() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    state.add_scan_warning(ScanSource::Aur, "Official mirror unreachable");
 
-    // Verify constants are what we expect
-    assert_eq!(OFFICIAL_SCAN_FAILURE_MARKER, "Official");
-    assert_eq!(AUR_SCAN_FAILURE_MARKER, "AUR");
+    // Only the reported source matters, not the warning text
+    assert!(!state.has_official_scan_failed());
 }
 
 // Phase 2: UI Integration Tests for dependency warnings
 
 #[test]
 fn test_set_dependency_conflicts_shows_modal() {
-    use par_tui::core::dependency::DependencyConflict;
-
     let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
     let mut state = AppState::new(packages, &[]);
 
@@ -264,7 +289,9 @@ fn test_set_dependency_conflicts_shows_modal() {
 
     let conflicts = vec![DependencyConflict {
         ignored_package: "glibc".to_string(),
+        installed_version: Some("2.40-1".to_string()),
         required_by: vec!["systemd".to_string()],
+        violations: Vec::new(),
     }];
 
     state.set_dependency_conflicts(conflicts);
@@ -273,6 +300,200 @@ fn test_set_dependency_conflicts_shows_modal() {
     assert_eq!(state.dependency_conflicts.len(), 1);
 }
 
+#[test]
+fn test_set_library_breakages_shows_modal() {
+    let packages = vec![make_test_package("icu", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+
+    state.set_library_breakages(Vec::new());
+    assert!(!state.show_dependency_warning);
+    assert!(!state.has_conflicts());
+
+    state.set_library_breakages(vec![LibraryBreakage {
+        package: "aur-tool".to_string(),
+        dependency: "libicuuc.so=74-64".to_string(),
+        provider: "icu".to_string(),
+    }]);
+
+    assert!(state.show_dependency_warning);
+    assert!(state.has_conflicts());
+}
+
+fn make_conflict(ignored: &str, required_by: &[&str], violated_by: &[&str]) -> DependencyConflict {
+    DependencyConflict {
+        ignored_package: ignored.to_string(),
+        installed_version: Some("1.9-1".to_string()),
+        required_by: required_by.iter().map(ToString::to_string).collect(),
+        violations: violated_by
+            .iter()
+            .map(|name| ConstraintViolation {
+                package: (*name).to_string(),
+                new_version: "3.0-1".to_string(),
+                constraint: format!("{ignored}>=2.0"),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_ignore_conflict_dependents() {
+    let packages = vec![
+        make_test_package("foo", PackageRepository::Official),
+        make_test_package("bar", PackageRepository::Official),
+        make_test_package("aur-tool", PackageRepository::Aur),
+        make_test_package("unrelated", PackageRepository::Official),
+    ];
+    let mut state = AppState::new(packages, &[]);
+    state.toggle_hold();
+    state.set_dependency_conflicts(vec![make_conflict("foo", &["aur-tool"], &["bar"])]);
+
+    state.ignore_conflict_dependents();
+
+    assert_eq!(state.get_ignored_packages(), vec!["foo", "bar", "aur-tool"]);
+    assert_eq!(
+        state.status_message.as_deref(),
+        Some("Also ignoring bar, aur-tool")
+    );
+}
+
+#[test]
+fn test_hold_all_dependents() {
+    let mut kirigami = make_test_package("kirigami", PackageRepository::Official);
+    kirigami.new_version = "6.8.0-2".to_string();
+    let packages = vec![
+        make_test_package("qt6-base", PackageRepository::Official),
+        make_test_package("qt6-declarative", PackageRepository::Official),
+        kirigami,
+    ];
+    let mut state = AppState::new(packages, &[]);
+    state.toggle_current_package();
+    state.hold_trees = vec![HoldNode {
+        package: "qt6-base".to_string(),
+        dependents: vec![HoldNode {
+            package: "qt6-declarative".to_string(),
+            dependents: vec![HoldNode {
+                package: "kirigami".to_string(),
+                dependents: Vec::new(),
+            }],
+        }],
+    }];
+
+    state.hold_all_dependents();
+
+    assert_eq!(
+        state.get_ignored_packages(),
+        vec!["qt6-base", "qt6-declarative", "kirigami"]
+    );
+    assert!(state.get_holds().is_empty());
+    assert!(state.packages[2].hold.is_none());
+    assert_eq!(
+        state.status_message.as_deref(),
+        Some("Holding back qt6-declarative, kirigami for this run")
+    );
+}
+
+#[test]
+fn test_hold_all_dependents_is_not_saved() {
+    let packages = vec![
+        make_test_package("qt6-base", PackageRepository::Official),
+        make_test_package("kirigami", PackageRepository::Official),
+    ];
+    let exclude = ExcludeConfig::default();
+
+    let mut state = AppState::new_loading();
+    state.load_exclusions(&exclude, 0);
+    state.set_packages(packages, &exclude.permanent);
+    state.toggle_current_package();
+    state.hold_trees = vec![HoldNode {
+        package: "qt6-base".to_string(),
+        dependents: vec![HoldNode {
+            package: "kirigami".to_string(),
+            dependents: Vec::new(),
+        }],
+    }];
+    state.hold_all_dependents();
+
+    assert!(state.packages[1].is_temporarily_ignored);
+    assert!(state.changed_exclusions(&exclude).is_none());
+}
+
+#[test]
+fn test_unignore_conflicting_releases_hold_and_snooze() {
+    let packages = vec![
+        make_test_package("foo", PackageRepository::Official),
+        make_test_package("libxml2", PackageRepository::Official),
+        make_test_package("bar", PackageRepository::Official),
+    ];
+    let mut state = AppState::new(packages, &[]);
+    state.toggle_hold();
+    state.move_cursor_down();
+    state.toggle_snooze(NOW, 7);
+    state.set_dependency_conflicts(vec![make_conflict("foo", &[], &["bar"])]);
+    state.set_library_breakages(vec![LibraryBreakage {
+        package: "libxml2".to_string(),
+        dependency: "libicuuc.so=74-64".to_string(),
+        provider: "icu".to_string(),
+    }]);
+
+    state.unignore_conflicting();
+
+    assert!(state.get_ignored_packages().is_empty());
+    assert!(state.get_holds().is_empty());
+    assert!(state.get_snoozes().is_empty());
+    assert_eq!(
+        state.status_message.as_deref(),
+        Some("Updating foo, libxml2")
+    );
+}
+
+#[test]
+fn test_unignore_conflicting_keeps_permanent_exclusions() {
+    let packages = vec![
+        make_test_package("foo", PackageRepository::Official),
+        make_test_package("bar", PackageRepository::Official),
+    ];
+    let mut state = AppState::new(packages, &["foo".to_string()]);
+    state.set_dependency_conflicts(vec![make_conflict("foo", &[], &["bar"])]);
+
+    state.unignore_conflicting();
+
+    assert_eq!(state.get_ignored_packages(), vec!["foo"]);
+    assert_eq!(
+        state.status_message.as_deref(),
+        Some("Nothing to un-ignore (foo still excluded)")
+    );
+}
+
+#[test]
+fn test_dependency_recheck_clears_warning_and_extends_status() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    state.set_dependency_problems(DependencyProblems {
+        conflicts: vec![DependencyConflict {
+            ignored_package: "glibc".to_string(),
+            installed_version: None,
+            required_by: vec!["pkg1".to_string()],
+            violations: Vec::new(),
+        }],
+        breakages: Vec::new(),
+        hold_trees: vec![HoldNode {
+            package: "glibc".to_string(),
+            dependents: Vec::new(),
+        }],
+    });
+    assert!(state.show_dependency_warning);
+    state.status_message = Some("Ignoring pkg1".to_string());
+
+    state.finish_dependency_recheck("no dependency conflicts remain");
+
+    assert!(state.dependency_conflicts.is_empty());
+    assert!(state.hold_trees.is_empty());
+    assert_eq!(
+        state.status_message.as_deref(),
+        Some("Ignoring pkg1; no dependency conflicts remain")
+    );
+}
+
 #[test]
 fn test_toggle_dependency_warning() {
     let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
@@ -289,8 +510,6 @@ fn test_toggle_dependency_warning() {
 
 #[test]
 fn test_has_conflicts() {
-    use par_tui::core::dependency::DependencyConflict;
-
     let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
     let mut state = AppState::new(packages, &[]);
 
@@ -298,7 +517,9 @@ fn test_has_conflicts() {
 
     state.dependency_conflicts.push(DependencyConflict {
         ignored_package: "test".to_string(),
+        installed_version: None,
         required_by: vec!["dep".to_string()],
+        violations: Vec::new(),
     });
 
     assert!(state.has_conflicts());
@@ -382,6 +603,82 @@ fn test_reverse_deps_cache_error_handling() {
     assert_eq!(err2, Some("Still not found".to_string()));
 }
 
+#[test]
+fn test_prefetch_required_by_batches_uncached() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    state
+        .reverse_deps_cache
+        .insert("bash".to_string(), vec!["base".to_string()]);
+
+    let ignored = ["glibc", "bash", "missing"].map(String::from);
+    let mut queried = Vec::new();
+    let missing = state.prefetch_required_by(&ignored, |names| {
+        queried = names.to_vec();
+        Ok(std::collections::HashMap::from([(
+            "glibc".to_string(),
+            vec!["systemd".to_string()],
+        )]))
+    });
+
+    // Cached packages are not queried again; missing ones are reported per name
+    assert_eq!(queried, vec!["glibc", "missing"]);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing["missing"], "Package 'missing' not found");
+
+    let (deps, err) = state.get_or_fetch_required_by("glibc", || panic!("should be cached"));
+    assert_eq!(deps, vec!["systemd"]);
+    assert!(err.is_none());
+
+    // Nothing left to fetch
+    let missing = state.prefetch_required_by(&ignored[..2], |_| panic!("should be cached"));
+    assert!(missing.is_empty());
+}
+
+#[test]
+fn test_prefetch_required_by_batch_failure() {
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+
+    let ignored = ["glibc", "bash"].map(String::from);
+    let missing = state.prefetch_required_by(&ignored, |_| Err("timed out".to_string()));
+
+    assert_eq!(missing.len(), 2);
+    assert_eq!(missing["bash"], "timed out");
+    assert!(state.reverse_deps_cache.is_empty());
+}
+
+#[test]
+fn test_transaction_preview_scroll() {
+    use par_tui::models::transaction::TransactionPreview;
+
+    let packages = vec![make_test_package("pkg1", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    assert!(!state.has_transaction_preview());
+
+    state.set_transaction_preview(Ok(TransactionPreview {
+        removals: vec!["jack2".to_string()],
+        skipped: vec!["firefox".to_string()],
+        ..TransactionPreview::default()
+    }));
+    assert!(state.has_transaction_preview());
+
+    // Scrolling stops at the last item
+    for _ in 0..5 {
+        state.transaction_scroll_down();
+    }
+    assert_eq!(state.transaction_scroll, 2);
+    state.transaction_scroll_up();
+    assert_eq!(state.transaction_scroll, 1);
+
+    // A new preview starts at the top
+    state.set_transaction_preview(Err("error: failed to prepare transaction".to_string()));
+    assert_eq!(state.transaction_scroll, 0);
+    state.transaction_scroll_down();
+    state.transaction_scroll_down();
+    assert_eq!(state.transaction_scroll, 1);
+}
+
 #[test]
 fn test_is_ready_states() {
     use par_tui::ui::app::LoadingState;
@@ -405,3 +702,821 @@ fn test_is_ready_states() {
     state.loading_state = LoadingState::Error("test".to_string());
     assert!(!state.is_ready());
 }
+
+#[test]
+fn test_source_progress_per_source() {
+    let mut state = AppState::new_loading();
+
+    state.set_source_progress(ScanSource::Official, "syncing databases...");
+    state.set_source_progress(ScanSource::Aur, "scanning...");
+    state.set_source_progress(ScanSource::Aur, "12 found");
+
+    assert_eq!(state.scan_progress.len(), 2);
+    assert_eq!(
+        state.scan_progress[0],
+        (ScanSource::Official, "syncing databases...".to_string())
+    );
+    assert_eq!(
+        state.scan_progress[1],
+        (ScanSource::Aur, "12 found".to_string())
+    );
+}
+
+#[test]
+fn test_auto_ignore_rules_applied_on_load() {
+    let mut rebuild = make_test_package("rebuild-git", PackageRepository::Aur);
+    rebuild.current_version = Some("1.0.0-1".to_string());
+    rebuild.new_version = "1.0.0-2".to_string();
+    let mut official = make_test_package("glibc", PackageRepository::Official);
+    official.current_version = Some("2.40-1".to_string());
+    official.new_version = "2.40-2".to_string();
+
+    let mut state = AppState::new_loading();
+    state.auto_ignore_rules = vec![AutoIgnoreRule {
+        kind: UpdateKind::PkgrelOnly,
+        repository: Some(PackageRepository::Aur),
+    }];
+    state.set_packages(vec![rebuild, official], &[]);
+
+    assert_eq!(state.packages[0].update_kind, UpdateKind::PkgrelOnly);
+    assert!(state.packages[0].is_auto_ignored);
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert!(!state.packages[1].is_auto_ignored);
+    assert_eq!(state.get_ignored_packages(), vec!["rebuild-git"]);
+
+    // Toggling hands the decision back to the user
+    state.toggle_current_package();
+    assert!(!state.packages[0].is_temporarily_ignored);
+    assert!(!state.packages[0].is_auto_ignored);
+}
+
+#[test]
+fn test_request_current_details_once() {
+    let packages = vec![
+        make_test_package("pkg1", PackageRepository::Official),
+        make_test_package("pkg2", PackageRepository::Aur),
+    ];
+    let mut state = AppState::new(packages, &[]);
+
+    let Some(pkg) = state.request_current_details() else {
+        panic!("uncached package should be requested");
+    };
+    assert_eq!(pkg.name, "pkg1");
+    // Still pending: the pane shows loading and no second lookup starts
+    assert!(state.request_current_details().is_none());
+    assert!(state.current_details().is_none());
+
+    state.finish_details(
+        pkg.name,
+        Ok(PackageDetails {
+            description: Some("first".to_string()),
+            ..PackageDetails::default()
+        }),
+    );
+    assert!(state.request_current_details().is_none());
+    assert!(matches!(
+        state.current_details(),
+        Some(Ok(details)) if details.description.as_deref() == Some("first")
+    ));
+
+    // Failures are cached per package as well
+    state.move_cursor_down();
+    let Some(pkg) = state.request_current_details() else {
+        panic!("uncached package should be requested");
+    };
+    state.finish_details(pkg.name, Err("pkg2 not found".to_string()));
+    assert!(state.request_current_details().is_none());
+    assert_eq!(
+        state.current_details(),
+        Some(&Err("pkg2 not found".to_string()))
+    );
+}
+
+#[test]
+fn test_finish_details_after_cursor_moved() {
+    let packages = vec![
+        make_test_package("pkg1", PackageRepository::Official),
+        make_test_package("pkg2", PackageRepository::Official),
+    ];
+    let mut state = AppState::new(packages, &[]);
+
+    let Some(pkg) = state.request_current_details() else {
+        panic!("uncached package should be requested");
+    };
+    state.move_cursor_down();
+    state.finish_details(
+        pkg.name,
+        Ok(PackageDetails {
+            repository: Some("extra".to_string()),
+            ..PackageDetails::default()
+        }),
+    );
+
+    // The result lands on the package it was fetched for
+    assert!(state.current_details().is_none());
+    assert_eq!(
+        state.packages[0].package.sync_repo.as_deref(),
+        Some("extra")
+    );
+    assert!(state.details_cache.contains_key("pkg1"));
+}
+
+#[test]
+fn test_size_totals_exclude_ignored() {
+    let mut first = make_test_package("pkg1", PackageRepository::Official);
+    first.download_size = Some(1_000);
+    first.installed_size_delta = Some(400);
+    let mut second = make_test_package("pkg2", PackageRepository::Official);
+    second.download_size = Some(2_000);
+    second.installed_size_delta = Some(-100);
+
+    let mut state = AppState::new(vec![first, second], &[]);
+    assert_eq!(state.size_totals(), (3_000, 300, 0));
+
+    state.toggle_current_package();
+    assert_eq!(state.size_totals(), (2_000, -100, 0));
+}
+
+#[test]
+fn test_pattern_permanent_exclusion() {
+    let packages = vec![
+        make_test_package("foo-git", PackageRepository::Aur),
+        make_test_package("mesa", PackageRepository::Official),
+    ];
+    let permanent = vec!["*-git".to_string(), "old-removed-pkg".to_string()];
+
+    let mut state = AppState::new(packages, &permanent);
+
+    assert!(state.packages[0].is_permanently_ignored);
+    assert_eq!(state.packages[0].permanent_rule.as_deref(), Some("*-git"));
+    assert!(!state.packages[1].is_permanently_ignored);
+
+    // `p` on a pattern-covered package explains instead of toggling
+    state.toggle_permanent_ignore();
+    assert!(state.packages[0].is_permanently_ignored);
+    assert!(
+        state
+            .status_message
+            .as_deref()
+            .is_some_and(|msg| msg.contains("*-git"))
+    );
+    assert_eq!(permanent_names(&state), permanent);
+
+    // Literal entries are appended; patterns and unlisted entries are preserved
+    state.move_cursor_down();
+    state.toggle_permanent_ignore();
+    assert_eq!(
+        permanent_names(&state),
+        vec!["*-git", "old-removed-pkg", "mesa"]
+    );
+    assert_eq!(state.packages[1].permanent_rule.as_deref(), Some("mesa"));
+}
+
+#[test]
+fn test_removing_literal_falls_back_to_pattern() {
+    let packages = vec![make_test_package("foo-git", PackageRepository::Aur)];
+    let permanent = vec!["foo-git".to_string(), "*-git".to_string()];
+
+    let mut state = AppState::new(packages, &permanent);
+    assert_eq!(state.packages[0].permanent_rule.as_deref(), Some("foo-git"));
+
+    state.toggle_permanent_ignore();
+
+    assert!(state.packages[0].is_permanently_ignored);
+    assert_eq!(state.packages[0].permanent_rule.as_deref(), Some("*-git"));
+    assert_eq!(permanent_names(&state), vec!["*-git"]);
+    assert!(state.status_message.is_some());
+}
+
+#[test]
+fn test_toggle_hold() {
+    let mut mesa = make_test_package("mesa", PackageRepository::Official);
+    mesa.new_version = "1:24.1.0-2".to_string();
+    let mut state = AppState::new(vec![mesa], &[]);
+
+    state.toggle_hold();
+
+    assert_eq!(
+        state.get_holds(),
+        vec![HoldRule {
+            name: "mesa".to_string(),
+            version: "1:24.1.0".to_string(),
+        }]
+    );
+    assert!(state.packages[0].hold.is_some());
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert_eq!(state.get_ignored_packages(), vec!["mesa"]);
+
+    state.toggle_hold();
+
+    assert!(state.get_holds().is_empty());
+    assert!(state.packages[0].hold.is_none());
+    assert!(!state.packages[0].is_temporarily_ignored);
+}
+
+#[test]
+fn test_toggle_hold_restores_manual_ignore() {
+    let mesa = make_test_package("mesa", PackageRepository::Official);
+    let mut state = AppState::new(vec![mesa], &[]);
+    state.toggle_current_package();
+
+    state.toggle_hold();
+    state.toggle_hold();
+
+    assert!(state.packages[0].hold.is_none());
+    assert!(state.packages[0].is_temporarily_ignored);
+}
+
+#[test]
+fn test_toggle_current_package_keeps_hold() {
+    let mesa = make_test_package("mesa", PackageRepository::Official);
+    let mut state = AppState::new(vec![mesa], &[]);
+    state.toggle_hold();
+
+    state.toggle_current_package();
+
+    assert!(state.packages[0].hold.is_some());
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert_eq!(state.get_holds().len(), 1);
+    assert!(
+        state
+            .status_message
+            .as_deref()
+            .is_some_and(|message| message.contains("press h"))
+    );
+}
+
+#[test]
+fn test_holds_applied_on_load() {
+    let mut held = make_test_package("mesa", PackageRepository::Official);
+    held.new_version = "24.1.0-1".to_string();
+    let mut moved_on = make_test_package("linux-lts", PackageRepository::Official);
+    moved_on.new_version = "6.6.61-1".to_string();
+
+    let mut state = AppState::new_loading();
+    state.holds = vec![
+        HoldRule {
+            name: "mesa".to_string(),
+            version: "24.1.0".to_string(),
+        },
+        HoldRule {
+            name: "linux-lts".to_string(),
+            version: "6.6.60".to_string(),
+        },
+    ];
+    state.set_packages(vec![held, moved_on], &[]);
+
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert!(state.packages[0].hold.is_some());
+    assert!(!state.packages[1].is_temporarily_ignored);
+    assert!(state.packages[1].hold.is_none());
+}
+
+#[test]
+fn test_toggle_snooze() {
+    let mesa = make_test_package("mesa", PackageRepository::Official);
+    let mut state = AppState::new(vec![mesa], &[]);
+
+    state.toggle_snooze(NOW, 7);
+
+    assert_eq!(
+        state.get_snoozes(),
+        vec![SnoozeRule {
+            name: "mesa".to_string(),
+            until: "2026-10-31T09:30:45Z".to_string(),
+        }]
+    );
+    assert!(state.packages[0].snooze.is_some());
+    assert_eq!(state.get_ignored_packages(), vec!["mesa"]);
+
+    state.toggle_snooze(NOW, 7);
+
+    assert!(state.get_snoozes().is_empty());
+    assert!(state.packages[0].snooze.is_none());
+    assert!(!state.packages[0].is_temporarily_ignored);
+}
+
+#[test]
+fn test_toggle_snooze_skips_held_package() {
+    let mesa = make_test_package("mesa", PackageRepository::Official);
+    let mut state = AppState::new(vec![mesa], &[]);
+    state.toggle_hold();
+
+    state.toggle_snooze(NOW, 7);
+
+    assert!(state.get_snoozes().is_empty());
+    assert!(state.packages[0].hold.is_some());
+}
+
+#[test]
+fn test_toggle_current_package_keeps_snooze() {
+    let mesa = make_test_package("mesa", PackageRepository::Official);
+    let mut state = AppState::new(vec![mesa], &[]);
+    state.toggle_snooze(NOW, 7);
+
+    state.toggle_current_package();
+
+    assert!(state.packages[0].snooze.is_some());
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert_eq!(state.get_snoozes().len(), 1);
+    assert!(
+        state
+            .status_message
+            .as_deref()
+            .is_some_and(|message| message.contains("press s"))
+    );
+}
+
+#[test]
+fn test_snoozes_applied_on_load() {
+    let mut state = AppState::new_loading();
+    state.snoozes = vec![SnoozeRule {
+        name: "mesa".to_string(),
+        until: "2026-10-31".to_string(),
+    }];
+    state.set_packages(
+        vec![
+            make_test_package("mesa", PackageRepository::Official),
+            make_test_package("linux", PackageRepository::Official),
+        ],
+        &[],
+    );
+
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert!(state.packages[0].snooze.is_some());
+    assert!(!state.packages[0].is_auto_ignored);
+    assert!(!state.packages[1].is_temporarily_ignored);
+}
+
+#[test]
+fn test_permanent_ignore_with_note() {
+    let packages = vec![make_test_package(
+        "nvidia-dkms",
+        PackageRepository::Official,
+    )];
+    let mut state = AppState::new(packages, &[]);
+
+    state.begin_permanent_ignore();
+    assert_eq!(state.note_input.as_deref(), Some(""));
+    assert!(!state.packages[0].is_permanently_ignored);
+
+    if let Some(input) = state.note_input.as_mut() {
+        input.push_str("  breaks suspend  ");
+    }
+    state.confirm_permanent_note(NOW);
+
+    assert!(state.note_input.is_none());
+    assert!(state.packages[0].is_permanently_ignored);
+    assert_eq!(
+        state.get_permanent_excludes(),
+        vec![PermanentExclude {
+            name: "nvidia-dkms".to_string(),
+            note: Some("breaks suspend".to_string()),
+            added: Some("2026-10-24".to_string()),
+        }]
+    );
+    assert_eq!(
+        state
+            .permanent_entry(&state.packages[0])
+            .and_then(|entry| entry.note.as_deref()),
+        Some("breaks suspend")
+    );
+
+    // Removing an exclusion does not prompt
+    state.begin_permanent_ignore();
+    assert!(state.note_input.is_none());
+    assert!(state.get_permanent_excludes().is_empty());
+}
+
+#[test]
+fn test_permanent_note_empty_or_cancelled() {
+    let packages = vec![make_test_package("mesa", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+
+    state.begin_permanent_ignore();
+    state.cancel_permanent_note();
+    assert!(state.note_input.is_none());
+    assert!(!state.packages[0].is_permanently_ignored);
+
+    state.begin_permanent_ignore();
+    state.confirm_permanent_note(NOW);
+    assert_eq!(
+        state.get_permanent_excludes(),
+        vec![PermanentExclude {
+            name: "mesa".to_string(),
+            note: None,
+            added: Some("2026-10-24".to_string()),
+        }]
+    );
+}
+
+fn make_grouped_package(name: &str, repo: &str, groups: &[&str]) -> Package {
+    let mut package = make_test_package(name, PackageRepository::Official);
+    package.sync_repo = Some(repo.to_string());
+    package.groups = groups.iter().map(ToString::to_string).collect();
+    package
+}
+
+#[test]
+fn test_toggle_group_ignore() {
+    let packages = vec![
+        make_grouped_package("nautilus", "extra", &["gnome"]),
+        make_grouped_package("gnome-shell", "extra", &["gnome"]),
+        make_grouped_package("mesa", "extra", &[]),
+    ];
+    let mut state = AppState::new(packages, &[]);
+
+    state.toggle_group_ignore();
+
+    assert_eq!(state.get_ignored_groups(), vec!["gnome"]);
+    assert_eq!(
+        state.packages[1].ignore_set,
+        Some(IgnoreSet::Group("gnome".to_string()))
+    );
+    assert_eq!(
+        state.get_ignored_packages(),
+        vec!["nautilus", "gnome-shell"]
+    );
+
+    state.toggle_group_ignore();
+
+    assert!(state.get_ignored_groups().is_empty());
+    assert!(state.get_ignored_packages().is_empty());
+
+    // Packages without a group only get an explanation
+    state.move_cursor_down();
+    state.move_cursor_down();
+    state.toggle_group_ignore();
+    assert!(state.get_ignored_groups().is_empty());
+    assert!(state.status_message.is_some());
+}
+
+#[test]
+fn test_toggle_group_ignore_steps_through_groups() {
+    let packages = vec![make_grouped_package(
+        "qt6-base",
+        "extra",
+        &["qt6", "kde-frameworks"],
+    )];
+    let mut state = AppState::new(packages, &[]);
+
+    state.toggle_group_ignore();
+    assert_eq!(state.get_ignored_groups(), vec!["qt6"]);
+
+    state.toggle_group_ignore();
+    assert_eq!(state.get_ignored_groups(), vec!["kde-frameworks"]);
+    assert_eq!(
+        state.packages[0].ignore_set,
+        Some(IgnoreSet::Group("kde-frameworks".to_string()))
+    );
+
+    state.toggle_group_ignore();
+    assert!(state.get_ignored_groups().is_empty());
+    assert!(!state.packages[0].is_temporarily_ignored);
+}
+
+#[test]
+fn test_toggle_current_package_keeps_ignore_set() {
+    let packages = vec![make_grouped_package("nautilus", "extra", &["gnome"])];
+    let mut state = AppState::new(packages, &[]);
+    state.toggle_group_ignore();
+
+    state.toggle_current_package();
+
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert_eq!(state.get_ignored_packages(), vec!["nautilus"]);
+    assert!(
+        state
+            .status_message
+            .as_deref()
+            .is_some_and(|message| message.contains("press g"))
+    );
+}
+
+#[test]
+fn test_toggle_repo_ignore_keeps_manual_toggles() {
+    let packages = vec![
+        make_grouped_package("lib32-mesa", "multilib", &[]),
+        make_grouped_package("lib32-glibc", "multilib", &[]),
+        make_grouped_package("mesa", "extra", &[]),
+    ];
+    let mut state = AppState::new(packages, &[]);
+    state.move_cursor_down();
+    state.move_cursor_down();
+    state.toggle_current_package();
+    state.cursor_position = 0;
+
+    state.toggle_repo_ignore();
+
+    assert_eq!(
+        state.packages[1].ignore_set,
+        Some(IgnoreSet::Repo("multilib".to_string()))
+    );
+    assert_eq!(
+        state.get_ignored_packages(),
+        vec!["lib32-mesa", "lib32-glibc", "mesa"]
+    );
+
+    state.toggle_repo_ignore();
+
+    assert_eq!(
+        state.status_message.as_deref(),
+        Some("No longer ignoring repository multilib")
+    );
+    // The manually ignored package outside the repository stays ignored
+    assert_eq!(state.get_ignored_packages(), vec!["mesa"]);
+}
+
+#[test]
+fn test_ignore_sets_applied_on_load() {
+    let mut state = AppState::new_loading();
+    state.ignored_repos = vec!["multilib".to_string()];
+    state.holds = vec![HoldRule {
+        name: "lib32-glibc".to_string(),
+        version: "2.0.0".to_string(),
+    }];
+    state.set_packages(
+        vec![
+            make_grouped_package("lib32-mesa", "multilib", &[]),
+            make_grouped_package("lib32-glibc", "multilib", &[]),
+        ],
+        &[],
+    );
+
+    assert!(state.packages[0].is_temporarily_ignored);
+    assert!(state.packages[0].ignore_set.is_some());
+    // Holds take precedence over ignored repositories
+    assert!(state.packages[1].hold.is_some());
+    assert!(state.packages[1].ignore_set.is_none());
+}
+
+#[test]
+fn test_pacman_conf_ignores_are_locked() {
+    let mut state = AppState::new_loading();
+    state.pacman_conf = PacmanConf {
+        ignore_pkg: vec!["linux*".to_string()],
+        ignore_group: vec!["gnome".to_string()],
+        ..PacmanConf::default()
+    };
+    state.set_packages(
+        vec![
+            make_test_package("linux-lts", PackageRepository::Official),
+            make_grouped_package("nautilus", "extra", &["gnome"]),
+            make_test_package("mesa", PackageRepository::Official),
+        ],
+        &["linux-lts".into()],
+    );
+
+    assert_eq!(
+        state.packages[0].pacman_ignore.as_deref(),
+        Some("IgnorePkg linux*")
+    );
+    // pacman.conf wins over the literal permanent exclusion
+    assert!(state.packages[0].permanent_rule.is_none());
+    assert!(state.packages[0].is_permanently_ignored);
+    assert_eq!(
+        state.packages[1].pacman_ignore.as_deref(),
+        Some("IgnoreGroup gnome")
+    );
+    assert!(state.packages[2].pacman_ignore.is_none());
+
+    state.toggle_current_package();
+    state.begin_permanent_ignore();
+    state.toggle_hold();
+    state.toggle_snooze(NOW, 7);
+
+    assert!(state.note_input.is_none());
+    assert!(state.holds.is_empty());
+    assert!(state.snoozes.is_empty());
+    assert_eq!(permanent_names(&state), vec!["linux-lts"]);
+    assert!(state.packages[0].is_permanently_ignored);
+    assert!(!state.packages[0].is_temporarily_ignored);
+    assert!(
+        state
+            .status_message
+            .as_deref()
+            .is_some_and(|msg| msg.contains("pacman.conf"))
+    );
+}
+
+#[test]
+fn test_pacman_conf_ignore_pkg_is_not_a_regex() {
+    let mut state = AppState::new_loading();
+    state.pacman_conf = PacmanConf {
+        ignore_pkg: vec!["re:^linux".to_string()],
+        ..PacmanConf::default()
+    };
+    state.set_packages(
+        vec![make_test_package("linux-lts", PackageRepository::Official)],
+        &[],
+    );
+
+    assert!(state.packages[0].pacman_ignore.is_none());
+    assert!(!state.packages[0].is_permanently_ignored);
+}
+
+#[test]
+fn test_repository_label() {
+    let mut official = make_test_package("mesa", PackageRepository::Official);
+    assert_eq!(repository_label(&official), "Official");
+    official.sync_repo = Some("extra".to_string());
+    assert_eq!(repository_label(&official), "extra");
+    assert_eq!(
+        repository_label(&make_test_package("yay", PackageRepository::Aur)),
+        "AUR"
+    );
+}
+
+#[test]
+fn test_repo_stats() {
+    let mut state = AppState::new_loading();
+    state.pacman_conf.repositories = vec![
+        "core".to_string(),
+        "extra".to_string(),
+        "multilib".to_string(),
+    ];
+    state.set_packages(
+        vec![
+            make_test_package("yay", PackageRepository::Aur),
+            make_grouped_package("lib32-mesa", "multilib", &[]),
+            make_grouped_package("firefox-nightly", "chaotic-aur", &[]),
+            make_grouped_package("mesa", "extra", &[]),
+            make_grouped_package("glibc", "core", &[]),
+            make_grouped_package("vulkan-icd-loader", "extra", &[]),
+            make_test_package("unknown-origin", PackageRepository::Official),
+        ],
+        &[],
+    );
+
+    assert_eq!(
+        state.repo_stats(),
+        vec![
+            ("core".to_string(), 1),
+            ("extra".to_string(), 2),
+            ("multilib".to_string(), 1),
+            ("Official".to_string(), 1),
+            ("chaotic-aur".to_string(), 1),
+            ("AUR".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_details_fill_in_sync_repo() {
+    let packages = vec![make_test_package("glibc", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+
+    state.finish_details(
+        "glibc".to_string(),
+        Ok(PackageDetails {
+            repository: Some("core".to_string()),
+            ..PackageDetails::default()
+        }),
+    );
+
+    assert_eq!(state.packages[0].package.sync_repo.as_deref(), Some("core"));
+}
+
+fn make_relations(depends: &[&str], required_by: &[&str]) -> PackageRelations {
+    PackageRelations {
+        depends: depends.iter().map(ToString::to_string).collect(),
+        required_by: required_by.iter().map(ToString::to_string).collect(),
+        optdepends: vec!["perl: for mtrace [installed]".to_string()],
+        provides: vec!["libc.so=6-64".to_string()],
+    }
+}
+
+#[test]
+fn test_graph_section_targets() {
+    assert_eq!(GraphSection::Depends.target("glibc>=2.38"), Some("glibc"));
+    assert_eq!(GraphSection::RequiredBy.target("bash"), Some("bash"));
+    assert_eq!(
+        GraphSection::OptDepends.target("perl: for mtrace [installed]"),
+        Some("perl")
+    );
+    assert_eq!(GraphSection::Provides.target("libc.so=6-64"), None);
+}
+
+#[test]
+fn test_graph_navigation() {
+    let packages = vec![make_test_package("glibc", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+
+    state.open_graph();
+    state.provider_index = Some(HashMap::new());
+    assert_eq!(state.request_graph_relations().as_deref(), Some("glibc"));
+    state.finish_relations(
+        "glibc".to_string(),
+        Ok(make_relations(
+            &["tzdata", "linux-api-headers>=4.10"],
+            &["bash"],
+        )),
+    );
+
+    assert_eq!(
+        state.graph_entries(),
+        vec![
+            (GraphSection::Depends, "tzdata"),
+            (GraphSection::Depends, "linux-api-headers>=4.10"),
+            (GraphSection::RequiredBy, "bash"),
+            (GraphSection::OptDepends, "perl: for mtrace [installed]"),
+            (GraphSection::Provides, "libc.so=6-64"),
+        ]
+    );
+
+    state.graph_cursor_down();
+    state.graph_enter();
+    let Some(graph) = &state.graph else {
+        panic!("graph should stay open");
+    };
+    assert_eq!(graph.focus, "linux-api-headers");
+    assert_eq!(graph.history, vec!["glibc"]);
+    assert_eq!(graph.cursor, 0);
+
+    // Not loaded yet for the new focus
+    assert!(state.graph_entries().is_empty());
+
+    state.graph_back();
+    let Some(graph) = &state.graph else {
+        panic!("graph should stay open");
+    };
+    assert_eq!(graph.focus, "glibc");
+    assert!(graph.history.is_empty());
+
+    state.close_graph();
+    assert!(state.graph.is_none());
+}
+
+#[test]
+fn test_graph_provides_are_not_navigable() {
+    let packages = vec![make_test_package("glibc", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    state.open_graph();
+    state.provider_index = Some(HashMap::new());
+    state.finish_relations("glibc".to_string(), Ok(make_relations(&[], &[])));
+
+    // optdepend, then provision
+    state.graph_cursor_down();
+    state.graph_cursor_down();
+    state.graph_enter();
+
+    let Some(graph) = &state.graph else {
+        panic!("graph should stay open");
+    };
+    assert_eq!(graph.focus, "glibc");
+    assert_eq!(graph.cursor, 1);
+}
+
+#[test]
+fn test_graph_lookup_errors_are_cached() {
+    let packages = vec![make_test_package("glibc", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    state.open_graph();
+
+    assert!(state.request_graph_relations().is_some());
+    // Already being fetched
+    assert!(state.request_graph_relations().is_none());
+    state.finish_relations(
+        "glibc".to_string(),
+        Err("Package 'glibc' not found".to_string()),
+    );
+
+    assert!(state.request_graph_relations().is_none());
+    assert!(matches!(state.graph_relations(), Some(Err(_))));
+    assert!(state.graph_entries().is_empty());
+}
+
+#[test]
+fn test_graph_enter_resolves_provisions() {
+    let packages = vec![make_test_package("glibc", PackageRepository::Official)];
+    let mut state = AppState::new(packages, &[]);
+    state.open_graph();
+    state.finish_relations(
+        "glibc".to_string(),
+        Ok(make_relations(&["sh", "libcrypto.so=3-64"], &[])),
+    );
+
+    // Nothing is navigable until the provider index is loaded
+    assert_eq!(state.graph_target(GraphSection::Depends, "sh"), None);
+    state.graph_enter();
+    assert_eq!(
+        state.graph.as_ref().map(|g| g.focus.as_str()),
+        Some("glibc")
+    );
+
+    state.provider_index = Some(HashMap::from([
+        ("sh".to_string(), "bash".to_string()),
+        ("libcrypto.so".to_string(), "openssl".to_string()),
+    ]));
+    assert_eq!(
+        state.graph_target(GraphSection::Depends, "libcrypto.so=3-64"),
+        Some("openssl")
+    );
+    assert_eq!(
+        state.graph_target(GraphSection::Depends, "tzdata"),
+        Some("tzdata")
+    );
+
+    state.graph_enter();
+    assert_eq!(state.graph.as_ref().map(|g| g.focus.as_str()), Some("bash"));
+}
@@ -0,0 +1,186 @@
+use par_tui::core::version::{
+    classify_update, matches_spec, split_changed, split_evr, strip_pkgrel, vercmp,
+};
+use par_tui::models::package::UpdateKind;
+use std::cmp::Ordering;
+
+/// Test table from pacman's `test/util/vercmptest.sh`.
+/// Each case is checked in both directions.
+const VERCMP_CASES: &[(&str, &str, i8)] = &[
+    // all similar length, no pkgrel
+    ("1.5.0", "1.5.0", 0),
+    ("1.5.1", "1.5.0", 1),
+    // mixed length
+    ("1.5.1", "1.5", 1),
+    // with pkgrel, simple
+    ("1.5.0-1", "1.5.0-1", 0),
+    ("1.5.0-1", "1.5.0-2", -1),
+    ("1.5.0-1", "1.5.1-1", -1),
+    ("1.5.0-2", "1.5.1-1", -1),
+    // with pkgrel, mixed lengths
+    ("1.5-1", "1.5.1-1", -1),
+    ("1.5-2", "1.5.1-1", -1),
+    ("1.5-2", "1.5.1-2", -1),
+    // mixed pkgrel inclusion
+    ("1.5", "1.5-1", 0),
+    ("1.5-1", "1.5", 0),
+    ("1.1-1", "1.1", 0),
+    ("1.0-1", "1.1", -1),
+    ("1.1-1", "1.0", 1),
+    // alphanumeric versions
+    ("1.5b-1", "1.5-1", -1),
+    ("1.5b", "1.5", -1),
+    ("1.5b-1", "1.5", -1),
+    ("1.5b", "1.5.1", -1),
+    // from the manpage
+    ("1.0a", "1.0alpha", -1),
+    ("1.0alpha", "1.0b", -1),
+    ("1.0b", "1.0beta", -1),
+    ("1.0beta", "1.0rc", -1),
+    ("1.0rc", "1.0", -1),
+    // going crazy? alpha-dotted versions
+    ("1.5.a", "1.5", 1),
+    ("1.5.b", "1.5.a", 1),
+    ("1.5.1", "1.5.b", 1),
+    // alpha dots and dashes
+    ("1.5.b-1", "1.5.b", 0),
+    ("1.5-1", "1.5.b", -1),
+    // same/similar content, differing separators
+    ("2.0", "2_0", 0),
+    ("2.0_a", "2_0.a", 0),
+    ("2.0a", "2.0.a", -1),
+    ("2___a", "2_a", 1),
+    // epoch included version comparisons
+    ("0:1.0", "0:1.0", 0),
+    ("0:1.0", "0:1.1", -1),
+    ("1:1.0", "0:1.0", 1),
+    ("1:1.0", "0:1.1", 1),
+    ("1:1.0", "2:1.1", -1),
+    // epoch + sometimes present pkgrel
+    ("1:1.0", "0:1.0-1", 1),
+    ("1:1.0-1", "0:1.1-1", 1),
+    // epoch included on one version
+    ("0:1.0", "1.0", 0),
+    ("0:1.1", "1.0", 1),
+    ("0:1.1", "1.1", 0),
+    ("1.0", "0:1.0", 0),
+    ("1.0", "0:1.1", -1),
+    ("1.1", "0:1.1", 0),
+    ("1:1.1", "1.1", 1),
+    ("1.1", "1:1.1", -1),
+];
+
+fn expected(sign: i8) -> Ordering {
+    sign.cmp(&0)
+}
+
+#[test]
+fn test_vercmp_pacman_table() {
+    for &(a, b, sign) in VERCMP_CASES {
+        assert_eq!(vercmp(a, b), expected(sign), "vercmp({a}, {b})");
+        assert_eq!(
+            vercmp(b, a),
+            expected(sign).reverse(),
+            "vercmp({b}, {a}) (reversed)"
+        );
+    }
+}
+
+#[test]
+fn test_vercmp_leading_zeros_and_long_numbers() {
+    assert_eq!(vercmp("1.001", "1.1"), Ordering::Equal);
+    assert_eq!(vercmp("1.10", "1.9"), Ordering::Greater);
+    assert_eq!(vercmp("20240101", "9999999"), Ordering::Greater);
+}
+
+#[test]
+fn test_vercmp_vcs_versions() {
+    assert_eq!(vercmp("r12.abc1234-1", "r13.def5678-1"), Ordering::Less);
+    assert_eq!(vercmp("6.9.1.arch1-1", "6.9.2.arch1-1"), Ordering::Less);
+}
+
+#[test]
+fn test_split_evr() {
+    assert_eq!(split_evr("1:2.0-3"), ("1", "2.0", Some("3")));
+    assert_eq!(split_evr("2.0"), ("0", "2.0", None));
+    assert_eq!(split_evr("6.9.1.arch1-1"), ("0", "6.9.1.arch1", Some("1")));
+    assert_eq!(split_evr(":1.0-1"), ("0", "1.0", Some("1")));
+}
+
+#[test]
+fn test_classify_update() {
+    let cases = [
+        ("1.2.3-1", "1.2.3-2", UpdateKind::PkgrelOnly),
+        ("1.2.3-1", "1.2.4-1", UpdateKind::Patch),
+        ("1.2.3-1", "1.3.0-1", UpdateKind::Minor),
+        ("1.2.3-1", "2.0.0-1", UpdateKind::Major),
+        ("1.2.3-1", "1:1.0.0-1", UpdateKind::Epoch),
+        ("1.2.3-2", "1.2.3-1", UpdateKind::Downgrade),
+        ("1.2-1", "1.2.1-1", UpdateKind::Patch),
+        ("6.9.1.arch1-1", "6.9.1.arch2-1", UpdateKind::Patch),
+        ("1.001-1", "1.1-2", UpdateKind::PkgrelOnly),
+    ];
+    for (current, new, kind) in cases {
+        assert_eq!(
+            classify_update(Some(current), new),
+            kind,
+            "{current} -> {new}"
+        );
+    }
+}
+
+#[test]
+fn test_classify_update_unknown_current() {
+    assert_eq!(classify_update(None, "1.0-1"), UpdateKind::Unknown);
+}
+
+#[test]
+fn test_split_changed() {
+    let cases = [
+        ("6.9.1.arch1-1", "6.9.2.arch1-1", ("6.9.", "2", ".arch1-1")),
+        ("1.2.3-1", "1.3.0-1", ("1.", "3.0", "-1")),
+        ("1.0-1", "1.0-2", ("1.0-", "2", "")),
+        ("1.0", "1.0.1", ("1.0", ".1", "")),
+        ("1.9-1", "1.10-1", ("1.", "10", "-1")),
+        ("r12.abc-1", "r13.def-1", ("r", "13.def", "-1")),
+        ("2.0-1", "2.0-1", ("2.0-1", "", "")),
+    ];
+    for (old, new, expected) in cases {
+        assert_eq!(split_changed(old, new), expected, "{old} -> {new}");
+    }
+}
+
+#[test]
+fn test_matches_spec() {
+    let cases = [
+        ("24.1.0-1", "24.1.0", true),
+        ("24.1.0-3", "=24.1.0", true),
+        ("24.1.1-1", "24.1.0", false),
+        ("24.1.0-2", "24.1.0-1", false),
+        ("6.6.12-1", "6.6.*", true),
+        ("6.6-1", "6.6.*", true),
+        ("6.60.1-1", "6.6.*", false),
+        ("6.7.1-1", ">=6.7", true),
+        ("6.6.99-1", ">=6.7", false),
+        ("6.6.99-1", "<6.7", true),
+        ("24.1.5-1", ">=24.1, <24.2", true),
+        ("24.2.0-1", ">=24.1, <24.2", false),
+        ("1:1.0-1", "1.0", false),
+        ("1:1.0-1", "1:1.*", true),
+        ("1.0-1", "", false),
+        ("1.0-1", ">=1.*", false),
+    ];
+    for (version, spec, expected) in cases {
+        assert_eq!(
+            matches_spec(version, spec),
+            expected,
+            "{version} vs '{spec}'"
+        );
+    }
+}
+
+#[test]
+fn test_strip_pkgrel() {
+    assert_eq!(strip_pkgrel("1:24.1.0-2"), "1:24.1.0");
+    assert_eq!(strip_pkgrel("r12.abc"), "r12.abc");
+}